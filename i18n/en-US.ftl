@@ -6,5 +6,29 @@ server_error = Server error. Please contact an administrator.
 register_success = Registration successful! Username: {$username}, Password: {$password}. You can type /chpass new_password to change your password.
 not_registered = You are not registered yet. Please enter /register to create an account.
 password_reset = Your username: {$username}, password has been reset. Your new password is: {$password}.
+password_unconfirmed = The server did not confirm the password change. Please try logging in with the new password, or run /chpass again.
 report_invalid_input = Your report information is incomplete. Please check and try again.
-report_success = Your report has been submitted successfully. Please wait for administrator processing.
\ No newline at end of file
+report_success = Your report has been submitted successfully. Please wait for administrator processing.
+permission_denied = You do not have permission to use this command.
+moderation_success = Command {$command} on {$target} was executed successfully.
+moderation_failed = Command {$command} on {$target} failed. Please check the server console.
+moderation_timeout = Command {$command} on {$target} timed out waiting for the server response.
+moderation_unknown = The server sent an unexpected reply to command {$command} on {$target}: {$message}
+report_not_found = This report could not be found.
+report_already_resolved = This report has already been resolved.
+report_accepted = Your report against {$username} has been reviewed and the player has been punished. Thank you for your report.
+report_rejected = Your report against {$username} has been reviewed and was rejected.
+moderation_rejected = The server rejected command {$command} on {$target}: {$message}
+announce_sent = The announcement has been sent to all players.
+announce_failed = Failed to send the announcement. Please check the server console.
+announce_scheduled = Announcement #{$id} scheduled for {$time}. Repeat interval: {$repeat} minutes (0 = no repeat).
+announce_invalid_time = Invalid time. Please use the format YYYY-MM-DD HH:MM (UTC) with a time in the future.
+announce_list_empty = There are no scheduled announcements.
+announce_cancelled = Announcement #{$id} has been cancelled.
+announce_not_found = No scheduled announcement #{$id} was found.
+profile_not_found = No rating data was found for {$username}.
+profile_title = {$username}'s Profile
+profile_rating = MMR {$score} · Rank #{$rank}
+profile_recent_changes = Recent MMR changes
+leaderboard_empty = No ranked players were found.
+leaderboard_title = Leaderboard
\ No newline at end of file
@@ -6,5 +6,29 @@ server_error = 서버 오류가 발생했습니다. 관리자에게 문의하세
 register_success = 등록 성공! 사용자 이름: {$username}, 비밀번호: {$password}. /chpass 새 비밀번호를 입력하여 비밀번호를 변경할 수 있습니다.
 not_registered = 아직 등록되지 않았습니다. /register 를 입력하여 계정을 등록하세요.
 password_reset = 사용자 이름: {$username}, 비밀번호가 재설정되었습니다. 새 비밀번호는 다음과 같습니다: {$password}.
+password_unconfirmed = 서버가 비밀번호 변경을 확인하지 않았습니다. 새 비밀번호로 로그인해 보시거나 /chpass 를 다시 실행해 주세요.
 report_invalid_input = 신고 정보가 완전하지 않습니다. 다시 확인해 주세요.
-report_success = 신고가 성공적으로 제출되었습니다. 관리자 처리를 기다려 주세요.
\ No newline at end of file
+report_success = 신고가 성공적으로 제출되었습니다. 관리자 처리를 기다려 주세요.
+permission_denied = 이 명령어를 사용할 권한이 없습니다.
+moderation_success = {$target}에 대한 {$command} 명령이 성공적으로 실행되었습니다.
+moderation_failed = {$target}에 대한 {$command} 명령이 실패했습니다. 서버 콘솔을 확인해 주세요.
+moderation_timeout = {$target}에 대한 {$command} 명령이 서버 응답 대기 중 시간 초과되었습니다.
+moderation_unknown = {$target}에 대한 {$command} 명령에 서버가 예상하지 못한 응답을 보냈습니다: {$message}
+report_not_found = 신고를 찾을 수 없습니다.
+report_already_resolved = 이미 처리된 신고입니다.
+report_accepted = {$username}에 대한 신고가 검토되었으며 해당 플레이어는 제재를 받았습니다. 신고해 주셔서 감사합니다.
+report_rejected = {$username}에 대한 신고가 검토되었으며 기각되었습니다.
+moderation_rejected = 서버가 {$target}에 대한 {$command} 명령을 거부했습니다: {$message}
+announce_sent = 모든 플레이어에게 공지를 보냈습니다.
+announce_failed = 공지 전송에 실패했습니다. 서버 콘솔을 확인해 주세요.
+announce_scheduled = 공지 #{$id}이(가) {$time}에 예약되었습니다. 반복 간격: {$repeat}분 (0 = 반복 없음).
+announce_invalid_time = 잘못된 시간입니다. YYYY-MM-DD HH:MM (UTC) 형식으로 미래의 시간을 입력해 주세요.
+announce_list_empty = 예약된 공지가 없습니다.
+announce_cancelled = 공지 #{$id}이(가) 취소되었습니다.
+announce_not_found = 예약된 공지 #{$id}을(를) 찾을 수 없습니다.
+profile_not_found = {$username}의 점수 정보를 찾을 수 없습니다.
+profile_title = {$username}의 프로필
+profile_rating = MMR {$score} · 순위 #{$rank}
+profile_recent_changes = 최근 MMR 변화
+leaderboard_empty = 순위 정보가 없습니다.
+leaderboard_title = 점수 순위표
\ No newline at end of file
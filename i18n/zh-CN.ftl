@@ -6,5 +6,29 @@ server_error = 服务器发生异常，请联系管理员。
 register_success = 注册成功！用户名：{$username}，密码：{$password}。您可以输入/chpass 新密码来更换您的密码。
 not_registered = 您尚未注册，请输入/register来注册账号。
 password_reset = 您的用户名：{$username}，已完成重置密码。您的新密码为：{$password}。
+password_unconfirmed = 服务器未确认密码变更。请尝试使用新密码登录，或再次执行 /chpass。
 report_invalid_input = 您的举报信息未填写完整，请重新确认一次。
-report_success = 您的举报已成功提交，请等待管理员处理。
\ No newline at end of file
+report_success = 您的举报已成功提交，请等待管理员处理。
+permission_denied = 您没有权限使用此指令。
+moderation_success = 已对 {$target} 成功执行 {$command} 指令。
+moderation_failed = 对 {$target} 执行 {$command} 指令失败，请确认服务器控制台。
+moderation_timeout = 对 {$target} 执行 {$command} 指令时等待服务器响应超时。
+moderation_unknown = 对 {$target} 执行 {$command} 指令时收到服务器的未知响应：{$message}
+report_not_found = 找不到此举报。
+report_already_resolved = 此举报已经处理完毕。
+report_accepted = 您对 {$username} 的举报已审核完成，该玩家已受到处罚，感谢您的举报。
+report_rejected = 您对 {$username} 的举报已审核完成，举报未被采纳。
+moderation_rejected = 服务器拒绝对 {$target} 执行 {$command} 指令：{$message}
+announce_sent = 公告已发送给所有玩家。
+announce_failed = 公告发送失败，请确认服务器控制台。
+announce_scheduled = 公告 #{$id} 已排程于 {$time} 发送。重复间隔：{$repeat} 分钟（0 为不重复）。
+announce_invalid_time = 无效的时间，请使用 YYYY-MM-DD HH:MM（UTC）格式并指定未来的时间。
+announce_list_empty = 目前没有排程中的公告。
+announce_cancelled = 公告 #{$id} 已取消。
+announce_not_found = 找不到排程公告 #{$id}。
+profile_not_found = 找不到 {$username} 的积分资料。
+profile_title = {$username} 的个人资料
+profile_rating = MMR {$score} · 排名 #{$rank}
+profile_recent_changes = 近期 MMR 变化
+leaderboard_empty = 目前没有任何排名资料。
+leaderboard_title = 积分排行榜
\ No newline at end of file
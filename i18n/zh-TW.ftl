@@ -6,5 +6,29 @@ server_error = 伺服器發生異常，請洽管理員。
 register_success = 註冊成功！使用者名稱：{$username}，密碼：{$password}。您可以輸入/chpass 新密碼來替換您的密碼。
 not_registered = 您尚未註冊，請輸入/register來註冊帳號。
 password_reset = 您的使用者名稱：{$username}，已完成重置密碼。您的新密碼為：{$password}。
+password_unconfirmed = 伺服器未確認密碼變更。請嘗試使用新密碼登入，或再次執行 /chpass。
 report_invalid_input = 您的檢舉資料沒有填寫完成，請重新確認一次。
-report_success = 您的檢舉已成功，請等待管理員處理。
\ No newline at end of file
+report_success = 您的檢舉已成功，請等待管理員處理。
+permission_denied = 您沒有權限使用此指令。
+moderation_success = 已對 {$target} 成功執行 {$command} 指令。
+moderation_failed = 對 {$target} 執行 {$command} 指令失敗，請確認伺服器主控台。
+moderation_timeout = 對 {$target} 執行 {$command} 指令時等待伺服器回應逾時。
+moderation_unknown = 對 {$target} 執行 {$command} 指令時收到伺服器的未知回應：{$message}
+report_not_found = 找不到此檢舉。
+report_already_resolved = 此檢舉已經處理完畢。
+report_accepted = 您對 {$username} 的檢舉已審核完成，該玩家已受到處分，感謝您的檢舉。
+report_rejected = 您對 {$username} 的檢舉已審核完成，檢舉未被採納。
+moderation_rejected = 伺服器拒絕對 {$target} 執行 {$command} 指令：{$message}
+announce_sent = 公告已發送給所有玩家。
+announce_failed = 公告發送失敗，請確認伺服器主控台。
+announce_scheduled = 公告 #{$id} 已排程於 {$time} 發送。重複間隔：{$repeat} 分鐘（0 為不重複）。
+announce_invalid_time = 無效的時間，請使用 YYYY-MM-DD HH:MM（UTC）格式並指定未來的時間。
+announce_list_empty = 目前沒有排程中的公告。
+announce_cancelled = 公告 #{$id} 已取消。
+announce_not_found = 找不到排程公告 #{$id}。
+profile_not_found = 找不到 {$username} 的積分資料。
+profile_title = {$username} 的個人資料
+profile_rating = MMR {$score} · 排名 #{$rank}
+profile_recent_changes = 近期 MMR 變化
+leaderboard_empty = 目前沒有任何排名資料。
+leaderboard_title = 積分排行榜
\ No newline at end of file
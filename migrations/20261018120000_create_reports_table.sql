@@ -0,0 +1,20 @@
+CREATE TABLE reports
+(
+    id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+    reporter_discord_id     TEXT    NOT NULL,
+    reporter_name           TEXT    NOT NULL,
+    reporter_locale         TEXT    NOT NULL,
+    reported_username       TEXT    NOT NULL,
+    reason                  TEXT    NOT NULL,
+    comment                 TEXT    NOT NULL DEFAULT '',
+    attachment_url          TEXT    NOT NULL,
+    attachment_content_type TEXT,
+    status                  TEXT    NOT NULL DEFAULT 'open',
+    ban_duration            INTEGER,
+    moderator_name          TEXT,
+    message_id              TEXT,
+    created_at              INTEGER NOT NULL DEFAULT (unixepoch()),
+    resolved_at             INTEGER
+);
+
+CREATE INDEX idx_reports_status ON reports (status);
@@ -0,0 +1,12 @@
+CREATE TABLE announcements
+(
+    id             INTEGER PRIMARY KEY AUTOINCREMENT,
+    message        TEXT    NOT NULL,
+    next_run_at    INTEGER NOT NULL,
+    repeat_minutes INTEGER,
+    created_by     TEXT    NOT NULL,
+    active         INTEGER NOT NULL DEFAULT 1,
+    created_at     INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX idx_announcements_active_next_run ON announcements (active, next_run_at);
@@ -0,0 +1,2 @@
+ALTER TABLE announcements ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE announcements ADD COLUMN last_error TEXT;
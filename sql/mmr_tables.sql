@@ -1,5 +1,8 @@
 -- MMR System Tables
 -- Run this script against the MySQL ghost database
+-- The script can be run again after updates: tables are only created and
+-- columns and indexes only added when missing (MySQL has no
+-- ADD COLUMN IF NOT EXISTS, so those statements check information_schema).
 
 -- Replace game_bonus_processed with game_mmr_processed
 CREATE TABLE IF NOT EXISTS game_mmr_processed (
@@ -24,4 +27,152 @@ CREATE TABLE IF NOT EXISTS score_change_logs (
     created_at DATETIME NOT NULL,
     INDEX idx_gameid (gameid),
     INDEX idx_name_category (name, category)
-);
\ No newline at end of file
+);
+
+-- Rating state for uncertainty-based algorithms (Glicko-2 deviation/volatility, TrueSkill sigma).
+-- Left NULL for categories rated with Elo.
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'scores' AND column_name = 'deviation') = 0,
+    'ALTER TABLE scores ADD COLUMN deviation DOUBLE NULL',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'scores' AND column_name = 'volatility') = 0,
+    'ALTER TABLE scores ADD COLUMN volatility DOUBLE NULL',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+-- Games excluded from rating by an admin
+CREATE TABLE IF NOT EXISTS game_mmr_voided (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    gameid INT NOT NULL UNIQUE,
+    reason VARCHAR(255) NOT NULL,
+    voided_at DATETIME NOT NULL
+);
+
+-- Performance modifier applied to each MMR change (1 = unmodified)
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'performance_modifier') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN performance_modifier DOUBLE NOT NULL DEFAULT 1 AFTER result_flag',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+-- Deviation and volatility around each change, NULL for Elo, so voiding can restore them
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'deviation_before') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN deviation_before DOUBLE NULL AFTER mmr_delta',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'deviation_after') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN deviation_after DOUBLE NULL AFTER deviation_before',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'volatility_before') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN volatility_before DOUBLE NULL AFTER deviation_after',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'volatility_after') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN volatility_after DOUBLE NULL AFTER volatility_before',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+-- Set when the change was reverted by voiding its game
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'score_change_logs' AND column_name = 'voided_at') = 0,
+    'ALTER TABLE score_change_logs ADD COLUMN voided_at DATETIME NULL',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+-- Seasons, the running season has ended_at NULL
+CREATE TABLE IF NOT EXISTS seasons (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    started_at DATETIME NOT NULL,
+    ended_at DATETIME NULL,
+    soft_reset_factor DOUBLE NULL
+);
+
+-- Final ratings of each ended season
+CREATE TABLE IF NOT EXISTS season_scores (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    season_id INT NOT NULL,
+    category VARCHAR(255) NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    server VARCHAR(255) NOT NULL,
+    score DOUBLE NOT NULL,
+    deviation DOUBLE NULL,
+    volatility DOUBLE NULL,
+    INDEX idx_season_category (season_id, category)
+);
+
+-- Activity tracking for inactivity decay
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'scores' AND column_name = 'last_played_at') = 0,
+    'ALTER TABLE scores ADD COLUMN last_played_at DATETIME NULL',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.columns
+     WHERE table_schema = DATABASE() AND table_name = 'scores' AND column_name = 'decayed_at') = 0,
+    'ALTER TABLE scores ADD COLUMN decayed_at DATETIME NULL',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+UPDATE scores s
+JOIN (
+    SELECT category, name, server, MAX(created_at) AS last_played_at
+    FROM score_change_logs
+    WHERE voided_at IS NULL
+    GROUP BY category, name, server
+) l ON l.category = s.category AND l.name = s.name AND l.server = s.server
+SET s.last_played_at = l.last_played_at
+WHERE s.last_played_at IS NULL;
+
+
+-- One rating per player, category and server, required by the worker's batched upserts.
+-- Merge or delete duplicate rows first if the ghost database already contains any.
+SET @sql = IF(
+    (SELECT COUNT(*) FROM information_schema.statistics
+     WHERE table_schema = DATABASE() AND table_name = 'scores' AND index_name = 'uq_scores_player') = 0,
+    'ALTER TABLE scores ADD UNIQUE INDEX uq_scores_player (category, name, server)',
+    'DO 0');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
\ No newline at end of file
@@ -1,16 +1,18 @@
+use crate::bot::scheduler;
 use crate::settings::CONFIG;
 use crate::telnet;
 use serenity::all::GatewayIntents;
 use serenity::Client;
 use sqlx::migrate::Migrator;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast::Receiver;
 use tokio::time::timeout;
 
 pub struct Bot {
     pub database: sqlx::SqlitePool,
-    pub telnet: telnet::ApiClient,
+    pub telnet: Arc<telnet::ApiClient>,
 }
 
 pub async fn start_discord_bot(
@@ -54,6 +56,15 @@ pub async fn start_discord_bot(
         .await
         .map_err(|e| format!("Couldn't run database migrations: {}", e))?;
 
+    let telnet_client = Arc::new(telnet_client);
+    telnet::set_api_client(telnet_client.clone());
+
+    scheduler::start_announcement_scheduler(
+        database.clone(),
+        telnet_client.clone(),
+        shutdown.resubscribe(),
+    );
+
     let bot = Bot {
         database,
         telnet: telnet_client,
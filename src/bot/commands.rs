@@ -1,4 +1,5 @@
 use crate::i18n;
+use crate::settings::CONFIG;
 use serenity::all::*;
 
 const COMMAND_REGISTER: &'static str = "register";
@@ -6,6 +7,15 @@ const COMMAND_FIND_ACCOUNT: &'static str = "find_account";
 const COMMAND_LINK_ACCOUNT: &'static str = "link_account";
 const COMMAND_CHANGE_PASSWORD: &'static str = "chpass";
 const COMMAND_REPORT: &'static str = "report";
+const COMMAND_BAN: &'static str = "ban";
+const COMMAND_UNBAN: &'static str = "unban";
+const COMMAND_IP_BAN: &'static str = "ipban";
+const COMMAND_UN_IP_BAN: &'static str = "unipban";
+const COMMAND_ANNOUNCE: &'static str = "announce";
+const COMMAND_ANNOUNCE_LIST: &'static str = "announce_list";
+const COMMAND_ANNOUNCE_CANCEL: &'static str = "announce_cancel";
+const COMMAND_PROFILE: &'static str = "profile";
+const COMMAND_LEADERBOARD: &'static str = "leaderboard";
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum CommandType {
@@ -13,6 +23,15 @@ pub enum CommandType {
     FindAccount,
     ChangePassword,
     Report,
+    Ban,
+    Unban,
+    IPBan,
+    UnIPBan,
+    Announce,
+    AnnounceList,
+    AnnounceCancel,
+    Profile,
+    Leaderboard,
 }
 
 impl CommandType {
@@ -22,6 +41,15 @@ impl CommandType {
             CommandType::FindAccount => COMMAND_FIND_ACCOUNT,
             CommandType::ChangePassword => COMMAND_CHANGE_PASSWORD,
             CommandType::Report => COMMAND_REPORT,
+            CommandType::Ban => COMMAND_BAN,
+            CommandType::Unban => COMMAND_UNBAN,
+            CommandType::IPBan => COMMAND_IP_BAN,
+            CommandType::UnIPBan => COMMAND_UN_IP_BAN,
+            CommandType::Announce => COMMAND_ANNOUNCE,
+            CommandType::AnnounceList => COMMAND_ANNOUNCE_LIST,
+            CommandType::AnnounceCancel => COMMAND_ANNOUNCE_CANCEL,
+            CommandType::Profile => COMMAND_PROFILE,
+            CommandType::Leaderboard => COMMAND_LEADERBOARD,
         }
     }
 }
@@ -41,18 +69,44 @@ impl std::str::FromStr for CommandType {
             COMMAND_FIND_ACCOUNT => Ok(CommandType::FindAccount),
             COMMAND_CHANGE_PASSWORD => Ok(CommandType::ChangePassword),
             COMMAND_REPORT => Ok(CommandType::Report),
+            COMMAND_BAN => Ok(CommandType::Ban),
+            COMMAND_UNBAN => Ok(CommandType::Unban),
+            COMMAND_IP_BAN => Ok(CommandType::IPBan),
+            COMMAND_UN_IP_BAN => Ok(CommandType::UnIPBan),
+            COMMAND_ANNOUNCE => Ok(CommandType::Announce),
+            COMMAND_ANNOUNCE_LIST => Ok(CommandType::AnnounceList),
+            COMMAND_ANNOUNCE_CANCEL => Ok(CommandType::AnnounceCancel),
+            COMMAND_PROFILE => Ok(CommandType::Profile),
+            COMMAND_LEADERBOARD => Ok(CommandType::Leaderboard),
             _ => Err("unknown command".to_string()),
         }
     }
 }
 
 pub fn get_commands() -> Vec<CreateCommand> {
-    vec![
+    let mut commands = vec![
         register(),
         find_account(),
         change_password(),
         report(),
-    ]
+        profile(),
+        leaderboard(),
+    ];
+
+    // Moderation commands are only offered when a moderator role is configured
+    if CONFIG.discord_moderator_role_id != 0 {
+        commands.extend([
+            ban(),
+            unban(),
+            ip_ban(),
+            un_ip_ban(),
+            announce(),
+            announce_list(),
+            announce_cancel(),
+        ]);
+    }
+
+    commands
 }
 
 fn register() -> CreateCommand {
@@ -154,3 +208,197 @@ fn report() -> CreateCommand {
             .required(false),
         )
 }
+
+fn ban() -> CreateCommand {
+    CreateCommand::new(CommandType::Ban)
+        .description("Ban player account")
+        .description_localized(i18n::LANG_ZH_TW, "封鎖玩家帳號")
+        .description_localized(i18n::LANG_ZH_CN, "封禁玩家账号")
+        .description_localized(i18n::LANG_KO_KR, "플레이어 계정 차단")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "username", "UserName")
+                .description_localized(i18n::LANG_ZH_TW, "使用者名稱")
+                .description_localized(i18n::LANG_ZH_CN, "用戶名")
+                .description_localized(i18n::LANG_KO_KR, "사용자 이름")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "duration",
+                "Ban duration in hours (0 = permanent)",
+            )
+            .description_localized(i18n::LANG_ZH_TW, "封鎖時數（0 為永久）")
+            .description_localized(i18n::LANG_ZH_CN, "封禁时数（0 为永久）")
+            .description_localized(i18n::LANG_KO_KR, "차단 시간 (0 = 영구)")
+            .min_int_value(0)
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "reason", "Reason for ban")
+                .description_localized(i18n::LANG_ZH_TW, "封鎖原因")
+                .description_localized(i18n::LANG_ZH_CN, "封禁原因")
+                .description_localized(i18n::LANG_KO_KR, "차단 이유")
+                .required(true),
+        )
+}
+
+fn unban() -> CreateCommand {
+    CreateCommand::new(CommandType::Unban)
+        .description("Unban player account")
+        .description_localized(i18n::LANG_ZH_TW, "解除封鎖玩家帳號")
+        .description_localized(i18n::LANG_ZH_CN, "解除封禁玩家账号")
+        .description_localized(i18n::LANG_KO_KR, "플레이어 계정 차단 해제")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "username", "UserName")
+                .description_localized(i18n::LANG_ZH_TW, "使用者名稱")
+                .description_localized(i18n::LANG_ZH_CN, "用戶名")
+                .description_localized(i18n::LANG_KO_KR, "사용자 이름")
+                .required(true),
+        )
+}
+
+fn ip_ban() -> CreateCommand {
+    CreateCommand::new(CommandType::IPBan)
+        .description("Ban IP address")
+        .description_localized(i18n::LANG_ZH_TW, "封鎖 IP 位址")
+        .description_localized(i18n::LANG_ZH_CN, "封禁 IP 地址")
+        .description_localized(i18n::LANG_KO_KR, "IP 주소 차단")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "ip", "IP address")
+                .description_localized(i18n::LANG_ZH_TW, "IP 位址")
+                .description_localized(i18n::LANG_ZH_CN, "IP 地址")
+                .description_localized(i18n::LANG_KO_KR, "IP 주소")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "duration",
+                "Ban duration in minutes (0 = permanent)",
+            )
+            .description_localized(i18n::LANG_ZH_TW, "封鎖分鐘數（0 為永久）")
+            .description_localized(i18n::LANG_ZH_CN, "封禁分钟数（0 为永久）")
+            .description_localized(i18n::LANG_KO_KR, "차단 시간(분) (0 = 영구)")
+            .min_int_value(0)
+            .required(true),
+        )
+}
+
+fn un_ip_ban() -> CreateCommand {
+    CreateCommand::new(CommandType::UnIPBan)
+        .description("Unban IP address")
+        .description_localized(i18n::LANG_ZH_TW, "解除封鎖 IP 位址")
+        .description_localized(i18n::LANG_ZH_CN, "解除封禁 IP 地址")
+        .description_localized(i18n::LANG_KO_KR, "IP 주소 차단 해제")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "ip", "IP address")
+                .description_localized(i18n::LANG_ZH_TW, "IP 位址")
+                .description_localized(i18n::LANG_ZH_CN, "IP 地址")
+                .description_localized(i18n::LANG_KO_KR, "IP 주소")
+                .required(true),
+        )
+}
+
+fn announce() -> CreateCommand {
+    CreateCommand::new(CommandType::Announce)
+        .description("Send an announcement to all players")
+        .description_localized(i18n::LANG_ZH_TW, "向所有玩家發送公告")
+        .description_localized(i18n::LANG_ZH_CN, "向所有玩家发送公告")
+        .description_localized(i18n::LANG_KO_KR, "모든 플레이어에게 공지 보내기")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "message", "Message")
+                .description_localized(i18n::LANG_ZH_TW, "公告內容")
+                .description_localized(i18n::LANG_ZH_CN, "公告内容")
+                .description_localized(i18n::LANG_KO_KR, "공지 내용")
+                .max_length(200)
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "time",
+                "Send at (UTC, YYYY-MM-DD HH:MM)",
+            )
+            .description_localized(i18n::LANG_ZH_TW, "發送時間（UTC，YYYY-MM-DD HH:MM）")
+            .description_localized(i18n::LANG_ZH_CN, "发送时间（UTC，YYYY-MM-DD HH:MM）")
+            .description_localized(i18n::LANG_KO_KR, "보낼 시간 (UTC, YYYY-MM-DD HH:MM)")
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "repeat",
+                "Repeat every N minutes",
+            )
+            .description_localized(i18n::LANG_ZH_TW, "每 N 分鐘重複發送")
+            .description_localized(i18n::LANG_ZH_CN, "每 N 分钟重复发送")
+            .description_localized(i18n::LANG_KO_KR, "N분마다 반복")
+            .min_int_value(1)
+            .required(false),
+        )
+}
+
+fn announce_list() -> CreateCommand {
+    CreateCommand::new(CommandType::AnnounceList)
+        .description("List scheduled announcements")
+        .description_localized(i18n::LANG_ZH_TW, "列出排程公告")
+        .description_localized(i18n::LANG_ZH_CN, "列出排程公告")
+        .description_localized(i18n::LANG_KO_KR, "예약된 공지 목록")
+}
+
+fn announce_cancel() -> CreateCommand {
+    CreateCommand::new(CommandType::AnnounceCancel)
+        .description("Cancel a scheduled announcement")
+        .description_localized(i18n::LANG_ZH_TW, "取消排程公告")
+        .description_localized(i18n::LANG_ZH_CN, "取消排程公告")
+        .description_localized(i18n::LANG_KO_KR, "예약된 공지 취소")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "id", "Announcement ID")
+                .description_localized(i18n::LANG_ZH_TW, "公告編號")
+                .description_localized(i18n::LANG_ZH_CN, "公告编号")
+                .description_localized(i18n::LANG_KO_KR, "공지 ID")
+                .required(true),
+        )
+}
+
+fn profile() -> CreateCommand {
+    CreateCommand::new(CommandType::Profile)
+        .description("Show a player's rating and recent MMR changes")
+        .description_localized(i18n::LANG_ZH_TW, "查詢玩家積分與近期 MMR 變化")
+        .description_localized(i18n::LANG_ZH_CN, "查询玩家积分与近期 MMR 变化")
+        .description_localized(i18n::LANG_KO_KR, "플레이어 점수와 최근 MMR 변화 보기")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "player",
+                "Player name (defaults to your account)",
+            )
+            .description_localized(i18n::LANG_ZH_TW, "玩家名稱（預設為您的帳號）")
+            .description_localized(i18n::LANG_ZH_CN, "玩家名称（默认为您的账号）")
+            .description_localized(i18n::LANG_KO_KR, "플레이어 이름 (기본값: 내 계정)")
+            .required(false),
+        )
+}
+
+fn leaderboard() -> CreateCommand {
+    CreateCommand::new(CommandType::Leaderboard)
+        .description("Show the top rated players")
+        .description_localized(i18n::LANG_ZH_TW, "查詢積分排行榜")
+        .description_localized(i18n::LANG_ZH_CN, "查询积分排行榜")
+        .description_localized(i18n::LANG_KO_KR, "점수 순위표 보기")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "category", "Category")
+                .description_localized(i18n::LANG_ZH_TW, "分類")
+                .description_localized(i18n::LANG_ZH_CN, "分类")
+                .description_localized(i18n::LANG_KO_KR, "카테고리")
+                .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "server", "Server")
+                .description_localized(i18n::LANG_ZH_TW, "伺服器")
+                .description_localized(i18n::LANG_ZH_CN, "服务器")
+                .description_localized(i18n::LANG_KO_KR, "서버")
+                .required(false),
+        )
+}
@@ -1,23 +1,77 @@
 use crate::bot::commands::CommandType;
-use crate::bot::query::{create_user, get_user_by_discord_id};
+use crate::bot::query::{
+    create_announcement, create_report, create_user, deactivate_announcement,
+    get_active_announcements, get_leaderboard, get_player_ranks, get_recent_score_changes,
+    get_report_by_id, get_user_by_discord_id, reopen_report, resolve_report,
+    set_report_message_id,
+};
 use crate::bot::response_code::ResponseCode;
+use crate::database::mysql_pool;
 use crate::i18n::I18N;
+use crate::model::report::{NewReport, Report, ReportStatus};
 use crate::model::user::User;
 use crate::settings::CONFIG;
 use crate::telnet::{ApiResult, Command};
 use crate::{telnet, util};
+use chrono::{NaiveDateTime, Utc};
 use rand::RngExt;
 use regex::Regex;
 use serenity::all::{
-    ChannelId, CommandDataOptionValue, CommandInteraction, Context, CreateEmbed,
-    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, Interaction,
-    Timestamp,
+    ButtonStyle, ChannelId, CommandDataOptionValue, CommandInteraction, ComponentInteraction,
+    Context, CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+    CreateMessage, EditInteractionResponse, Interaction, Member, RoleId, Timestamp, UserId,
 };
 use serenity::Error;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
 
+const LEADERBOARD_SIZE: i64 = 10;
+const PROFILE_RECENT_CHANGES: i64 = 5;
+
+const REPORT_ACCEPT_PREFIX: &str = "report_accept";
+const REPORT_REJECT_PREFIX: &str = "report_reject";
+
+/// Ban durations offered on the report embed, in hours (0 = permanent).
+const REPORT_BAN_DURATIONS: [(u32, &str); 3] = [
+    (24, "Ban 1 day"),
+    (168, "Ban 7 days"),
+    (0, "Ban permanently"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum ReportAction {
+    Accept(i64, u32),
+    Reject(i64),
+}
+
+impl ReportAction {
+    fn to_custom_id(self) -> String {
+        match self {
+            ReportAction::Accept(id, hours) => format!("{}:{}:{}", REPORT_ACCEPT_PREFIX, id, hours),
+            ReportAction::Reject(id) => format!("{}:{}", REPORT_REJECT_PREFIX, id),
+        }
+    }
+}
+
+impl FromStr for ReportAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let parse_id = |v: &str| v.parse::<i64>().map_err(|e| e.to_string());
+        match parts.as_slice() {
+            [REPORT_ACCEPT_PREFIX, id, hours] => Ok(ReportAction::Accept(
+                parse_id(id)?,
+                hours.parse::<u32>().map_err(|e| e.to_string())?,
+            )),
+            [REPORT_REJECT_PREFIX, id] => Ok(ReportAction::Reject(parse_id(id)?)),
+            _ => Err(format!("unknown custom id: {}", s)),
+        }
+    }
+}
+
 pub async fn handle_interaction(
     db: &sqlx::sqlite::SqlitePool,
     client: &telnet::ApiClient,
@@ -32,10 +86,25 @@ pub async fn handle_interaction(
                 CommandType::ChangePassword => {
                     handle_change_password(db, client, ctx, interaction).await?
                 }
-                CommandType::Report => handle_report(ctx, interaction).await?,
+                CommandType::Report => handle_report(db, ctx, interaction).await?,
+                CommandType::Ban
+                | CommandType::Unban
+                | CommandType::IPBan
+                | CommandType::UnIPBan => handle_moderation(client, ctx, interaction, cmd).await?,
+                CommandType::Announce => handle_announce(db, client, ctx, interaction).await?,
+                CommandType::AnnounceList => handle_announce_list(db, ctx, interaction).await?,
+                CommandType::AnnounceCancel => handle_announce_cancel(db, ctx, interaction).await?,
+                CommandType::Profile => handle_profile(db, ctx, interaction).await?,
+                CommandType::Leaderboard => handle_leaderboard(ctx, interaction).await?,
             },
             Err(err) => eprintln!("unknown interaction, ex:{:?}", err),
         },
+        Interaction::Component(component) => {
+            match ReportAction::from_str(&component.data.custom_id) {
+                Ok(action) => handle_report_component(db, client, ctx, component, action).await?,
+                Err(err) => eprintln!("unknown component, ex:{:?}", err),
+            }
+        }
         _ => eprintln!("unknown interaction"),
     }
 
@@ -146,23 +215,30 @@ async fn handle_change_password(
             .and_then(|opt| opt.value.as_str())
             .unwrap_or_default();
 
+        // The telnet queue can take longer than Discord waits for a first response
+        command_defer(ctx, command).await?;
+
         let username = match change_password(db, telnet, &discord_id, password).await {
             Ok(username) => username,
             Err(err) => {
                 let message = I18N.get(err.to_i18n_key(), locale);
-                command_send_message(ctx, command, message).await?;
+                command_edit_message(ctx, command, message).await?;
                 return Ok(());
             }
         };
 
         let args = vec![("username", username.as_str()), ("password", password)];
         let message = I18N.get_with_args(ResponseCode::PasswordReset.to_i18n_key(), locale, &args);
-        command_send_message(ctx, command, message).await?;
+        command_edit_message(ctx, command, message).await?;
     }
     Ok(())
 }
 
-async fn handle_report(ctx: &Context, interaction: &Interaction) -> serenity::Result<(), Error> {
+async fn handle_report(
+    db: &sqlx::sqlite::SqlitePool,
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
     if let Interaction::Command(command) = interaction {
         let locale = command.locale.as_str();
 
@@ -204,7 +280,7 @@ async fn handle_report(ctx: &Context, interaction: &Interaction) -> serenity::Re
             })
             .and_then(|id| command.data.resolved.attachments.get(id));
 
-        if username.is_empty() || reason_code.is_empty() || attachment.is_none() {
+        if !check_username_valid(username) || reason_code.is_empty() || attachment.is_none() {
             command_send_message(
                 ctx,
                 command,
@@ -233,6 +309,36 @@ async fn handle_report(ctx: &Context, interaction: &Interaction) -> serenity::Re
             }
         }
 
+        let attachment_data = match attachment {
+            Some(attachment_data) => attachment_data,
+            None => return Ok(()),
+        };
+
+        let new_report = NewReport {
+            reporter_discord_id: &command.user.id.to_string(),
+            reporter_name: &command.user.name,
+            reporter_locale: locale,
+            reported_username: username,
+            reason,
+            comment,
+            attachment_url: &attachment_data.url,
+            attachment_content_type: attachment_data.content_type.as_deref(),
+        };
+
+        let report = match create_report(db, &new_report).await {
+            Ok(report) => report,
+            Err(err) => {
+                println!("create report failed, ex:{}", err);
+                command_send_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
         command_send_message(
             ctx,
             command,
@@ -240,44 +346,878 @@ async fn handle_report(ctx: &Context, interaction: &Interaction) -> serenity::Re
         )
         .await?;
 
-        let mut embed = CreateEmbed::new()
-            .title("User Report")
-            .color(0xff0000) // 紅色
-            .field("Reported Player", username, false)
-            .field("Reason", reason, false)
-            .field("Reporter", &command.user.name, false)
-            .timestamp(Timestamp::now());
+        let message = CreateMessage::new()
+            .embed(build_report_embed(&report))
+            .components(build_report_components(&report));
 
-        if !comment.is_empty() {
-            embed = embed.field("Comment", comment, false);
+        let report_channel_id = ChannelId::new(CONFIG.discord_report_channel_id);
+
+        let sent = report_channel_id.send_message(&ctx.http, message).await?;
+
+        if let Err(err) = set_report_message_id(db, report.id, &sent.id.to_string()).await {
+            println!("save report message id failed, ex:{}", err);
         }
+    }
 
-        if let Some(attachment_data) = attachment {
-            embed = embed.field(
-                "Attachment",
-                format!("[Click Download]({})", attachment_data.url),
-                false,
+    Ok(())
+}
+
+async fn handle_report_component(
+    db: &sqlx::sqlite::SqlitePool,
+    telnet: &telnet::ApiClient,
+    ctx: &Context,
+    component: &ComponentInteraction,
+    action: ReportAction,
+) -> serenity::Result<(), Error> {
+    let locale = component.locale.as_str();
+
+    if !has_moderator_role(component.member.as_ref()) {
+        component_send_message(
+            ctx,
+            component,
+            I18N.get(ResponseCode::PermissionDenied.to_i18n_key(), locale),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let report_id = match action {
+        ReportAction::Accept(id, _) | ReportAction::Reject(id) => id,
+    };
+
+    let report = match get_report_by_id(db, report_id).await {
+        Ok(Some(report)) => report,
+        Ok(None) => {
+            component_send_message(
+                ctx,
+                component,
+                I18N.get(ResponseCode::ReportNotFound.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+        Err(err) => {
+            println!("query report failed, ex:{}", err);
+            component_send_message(
+                ctx,
+                component,
+                I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if report.status != ReportStatus::Open {
+        component_send_message(
+            ctx,
+            component,
+            I18N.get(ResponseCode::ReportAlreadyResolved.to_i18n_key(), locale),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // The name is sent as part of a console command, so it must not carry extra arguments
+    if !check_username_valid(&report.reported_username) {
+        component_send_message(
+            ctx,
+            component,
+            I18N.get(ResponseCode::InvalidInput.to_i18n_key(), locale),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let (status, ban_duration) = match action {
+        ReportAction::Accept(_, duration) => (ReportStatus::Accepted, Some(duration)),
+        ReportAction::Reject(_) => (ReportStatus::Rejected, None),
+    };
+
+    // Claim the report first so two moderators cannot both act on it
+    let resolved =
+        match resolve_report(db, report.id, status, ban_duration, &component.user.name).await {
+            Ok(Some(report)) => report,
+            Ok(None) => {
+                component_send_message(
+                    ctx,
+                    component,
+                    I18N.get(ResponseCode::ReportAlreadyResolved.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+            Err(err) => {
+                println!("resolve report failed, ex:{}", err);
+                component_send_message(
+                    ctx,
+                    component,
+                    I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+    if let Some(duration) = ban_duration {
+        // The telnet queue can take longer than Discord waits for a first response
+        component
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await?;
+
+        let ban_command = Command::Ban(
+            resolved.reported_username.clone(),
+            duration,
+            resolved.reason.clone(),
+        );
+
+        let response_code = moderation_response(telnet.send_command(ban_command).await);
+
+        if response_code != ResponseCode::ModerationSuccess {
+            if let Err(err) = reopen_report(db, resolved.id).await {
+                println!("reopen report failed, ex:{}", err);
+            }
+            let message = moderation_message(
+                &response_code,
+                CommandType::Ban,
+                &resolved.reported_username,
+                locale,
             );
+            component
+                .create_followup(
+                    &ctx.http,
+                    CreateInteractionResponseFollowup::new()
+                        .content(message)
+                        .ephemeral(true),
+                )
+                .await?;
+            return Ok(());
+        }
+
+        component
+            .edit_response(
+                &ctx.http,
+                EditInteractionResponse::new()
+                    .embed(build_report_embed(&resolved))
+                    .components(build_report_components(&resolved)),
+            )
+            .await?;
+    } else {
+        component
+            .create_response(
+                &ctx.http,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(build_report_embed(&resolved))
+                        .components(build_report_components(&resolved)),
+                ),
+            )
+            .await?;
+    }
+
+    let outcome_code = match resolved.status {
+        ReportStatus::Accepted => ResponseCode::ReportAccepted,
+        _ => ResponseCode::ReportRejected,
+    };
+
+    let reporter_id = match resolved.reporter_discord_id.parse::<u64>() {
+        Ok(id) => UserId::new(id),
+        Err(err) => {
+            println!("invalid reporter discord id, ex:{}", err);
+            return Ok(());
+        }
+    };
+
+    let dm = CreateMessage::new().content(I18N.get_with_arg(
+        outcome_code.to_i18n_key(),
+        &resolved.reporter_locale,
+        "username",
+        &resolved.reported_username,
+    ));
+
+    // Reporters may have DMs disabled, which should not fail the moderation.
+    if let Err(err) = reporter_id.direct_message(&ctx.http, dm).await {
+        println!("send report result to reporter failed, ex:{:?}", err);
+    }
+
+    Ok(())
+}
+
+fn build_report_embed(report: &Report) -> CreateEmbed {
+    let (title, color): (&str, u32) = match report.status {
+        ReportStatus::Open => ("User Report", 0xff0000), // 紅色
+        ReportStatus::Accepted => ("User Report - Accepted", 0x2ecc71), // 綠色
+        ReportStatus::Rejected => ("User Report - Rejected", 0x95a5a6), // 灰色
+    };
+
+    let timestamp = Timestamp::from_unix_timestamp(report.created_at as i64)
+        .unwrap_or_else(|_| Timestamp::now());
+
+    let mut embed = CreateEmbed::new()
+        .title(title)
+        .color(color)
+        .field("Reported Player", &report.reported_username, false)
+        .field("Reason", &report.reason, false)
+        .field("Reporter", &report.reporter_name, false)
+        .footer(CreateEmbedFooter::new(format!("Report #{}", report.id)))
+        .timestamp(timestamp);
+
+    if !report.comment.is_empty() {
+        embed = embed.field("Comment", &report.comment, false);
+    }
+
+    embed = embed.field(
+        "Attachment",
+        format!("[Click Download]({})", report.attachment_url),
+        false,
+    );
+
+    if report
+        .attachment_content_type
+        .as_ref()
+        .is_some_and(|ct| ct.starts_with("image/"))
+    {
+        embed = embed.image(&report.attachment_url);
+    }
+
+    if report.status != ReportStatus::Open {
+        let moderator = report.moderator_name.as_deref().unwrap_or("unknown");
+        let result = match (report.status, report.ban_duration) {
+            (ReportStatus::Accepted, Some(0)) => format!("Banned permanently by {}", moderator),
+            (ReportStatus::Accepted, Some(hours)) => {
+                format!("Banned for {} hours by {}", hours, moderator)
+            }
+            _ => format!("Rejected by {}", moderator),
+        };
+        embed = embed.field("Result", result, false);
+    }
+
+    embed
+}
+
+fn build_report_components(report: &Report) -> Vec<CreateActionRow> {
+    if report.status != ReportStatus::Open {
+        return Vec::new();
+    }
+
+    let buttons = REPORT_BAN_DURATIONS
+        .iter()
+        .map(|(hours, label)| {
+            CreateButton::new(ReportAction::Accept(report.id, *hours).to_custom_id())
+                .label(*label)
+                .style(ButtonStyle::Danger)
+        })
+        .chain(std::iter::once(
+            CreateButton::new(ReportAction::Reject(report.id).to_custom_id())
+                .label("Reject")
+                .style(ButtonStyle::Secondary),
+        ))
+        .collect();
+
+    vec![CreateActionRow::Buttons(buttons)]
+}
+
+async fn handle_moderation(
+    telnet: &telnet::ApiClient,
+    ctx: &Context,
+    interaction: &Interaction,
+    cmd: CommandType,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+
+        // The telnet queue can take longer than Discord waits for a first response
+        command_defer(ctx, command).await?;
+
+        if !has_moderator_role(command.member.as_deref()) {
+            command_edit_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::PermissionDenied.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let options = &command.data.options;
+
+        let get_str = |name: &str| {
+            options
+                .iter()
+                .find(|opt| opt.name == name)
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+
+        let duration = options
+            .iter()
+            .find(|opt| opt.name == "duration")
+            .and_then(|opt| opt.value.as_i64())
+            .unwrap_or(0)
+            .clamp(0, u32::MAX as i64) as u32;
+
+        let (target, telnet_command) = match cmd {
+            CommandType::Ban => {
+                let username = get_str("username");
+                let reason = get_str("reason");
+                let command = Command::Ban(username.clone(), duration, reason);
+                (username, command)
+            }
+            CommandType::Unban => {
+                let username = get_str("username");
+                (username.clone(), Command::Unban(username))
+            }
+            CommandType::IPBan => {
+                let ip = get_str("ip");
+                (ip.clone(), Command::IPBan(ip, duration))
+            }
+            CommandType::UnIPBan => {
+                let ip = get_str("ip");
+                (ip.clone(), Command::UnIPBan(ip))
+            }
+            _ => return Ok(()),
+        };
+
+        let valid_target = match cmd {
+            CommandType::IPBan | CommandType::UnIPBan => check_ip_valid(&target),
+            _ => check_username_valid(&target),
+        };
+
+        if !valid_target {
+            command_edit_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::InvalidInput.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        println!(
+            "moderator {} executed {} on {}",
+            command.user.name,
+            cmd.as_str(),
+            target
+        );
+
+        let response_code = moderation_response(telnet.send_command(telnet_command).await);
+        let message = moderation_message(&response_code, cmd, &target, locale);
+        command_edit_message(ctx, command, message).await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_announce(
+    db: &sqlx::sqlite::SqlitePool,
+    telnet: &telnet::ApiClient,
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+
+        // The telnet queue can take longer than Discord waits for a first response
+        command_defer(ctx, command).await?;
+
+        if !has_moderator_role(command.member.as_deref()) {
+            command_edit_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::PermissionDenied.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let options = &command.data.options;
+
+        let message = options
+            .iter()
+            .find(|opt| opt.name == "message")
+            .and_then(|opt| opt.value.as_str())
+            .unwrap_or_default()
+            .trim();
+
+        let time = options
+            .iter()
+            .find(|opt| opt.name == "time")
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+            .filter(|time| !time.is_empty());
+
+        let repeat_minutes = options
+            .iter()
+            .find(|opt| opt.name == "repeat")
+            .and_then(|opt| opt.value.as_i64())
+            .filter(|minutes| *minutes > 0)
+            .map(|minutes| minutes.min(u32::MAX as i64) as u32);
+
+        if message.is_empty() {
+            command_edit_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::InvalidInput.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let now = Utc::now().timestamp();
+
+        let send_at = match time {
+            Some(time) => match NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M") {
+                Ok(datetime) if datetime.and_utc().timestamp() > now => {
+                    Some(datetime.and_utc().timestamp())
+                }
+                _ => {
+                    command_edit_message(
+                        ctx,
+                        command,
+                        I18N.get(ResponseCode::AnnounceInvalidTime.to_i18n_key(), locale),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+
+        // Send right away unless the announcement starts later
+        if send_at.is_none() {
+            let result = telnet
+                .send_command(Command::Announce(message.to_string()))
+                .await;
+            if !matches!(result, Ok(ApiResult::Success(_))) {
+                println!("send announcement failed, ex:{:?}", result);
+                command_edit_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::AnnounceFailed.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
 
-            if attachment_data
-                .content_type
-                .as_ref()
-                .map_or(false, |ct| ct.starts_with("image/"))
+            if repeat_minutes.is_none() {
+                command_edit_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::AnnounceSent.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+        }
+
+        let next_run_at = match (send_at, repeat_minutes) {
+            (Some(send_at), _) => send_at,
+            (None, Some(minutes)) => now + minutes as i64 * 60,
+            (None, None) => return Ok(()),
+        };
+
+        let announcement =
+            match create_announcement(db, message, next_run_at, repeat_minutes, &command.user.name)
+                .await
             {
-                embed = embed.image(&attachment_data.url);
+                Ok(announcement) => announcement,
+                Err(err) => {
+                    println!("create announcement failed, ex:{}", err);
+                    command_edit_message(
+                        ctx,
+                        command,
+                        I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+            };
+
+        let id = announcement.id.to_string();
+        let next_run = format!("<t:{}:f>", announcement.next_run_at);
+        let repeat = repeat_minutes.unwrap_or(0).to_string();
+        let args = vec![
+            ("id", id.as_str()),
+            ("time", next_run.as_str()),
+            ("repeat", repeat.as_str()),
+        ];
+        let message =
+            I18N.get_with_args(ResponseCode::AnnounceScheduled.to_i18n_key(), locale, &args);
+        command_edit_message(ctx, command, message).await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_announce_list(
+    db: &sqlx::sqlite::SqlitePool,
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+
+        if !has_moderator_role(command.member.as_deref()) {
+            command_send_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::PermissionDenied.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let announcements = match get_active_announcements(db).await {
+            Ok(announcements) => announcements,
+            Err(err) => {
+                println!("query announcements failed, ex:{}", err);
+                command_send_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
             }
+        };
+
+        if announcements.is_empty() {
+            command_send_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::AnnounceListEmpty.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
         }
 
-        let message = CreateMessage::new().embed(embed);
+        let message = announcements
+            .iter()
+            .map(|a| {
+                let repeat = match a.repeat_minutes {
+                    Some(minutes) => format!(" (every {} min)", minutes),
+                    None => String::new(),
+                };
+                let failed = match &a.last_error {
+                    Some(error) if a.failed_attempts > 0 => {
+                        format!(" [{} failed attempts, last: {}]", a.failed_attempts, error)
+                    }
+                    _ => String::new(),
+                };
+                format!(
+                    "#{} <t:{}:f>{}{}: {}",
+                    a.id, a.next_run_at, repeat, failed, a.message
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        command_send_message(ctx, command, message).await?;
+    }
 
-        let report_channel_id = ChannelId::new(CONFIG.discord_report_channel_id);
+    Ok(())
+}
+
+async fn handle_announce_cancel(
+    db: &sqlx::sqlite::SqlitePool,
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+
+        if !has_moderator_role(command.member.as_deref()) {
+            command_send_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::PermissionDenied.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let id = command
+            .data
+            .options
+            .iter()
+            .find(|opt| opt.name == "id")
+            .and_then(|opt| opt.value.as_i64())
+            .unwrap_or(0);
+
+        let response_code = match deactivate_announcement(db, id).await {
+            Ok(true) => ResponseCode::AnnounceCancelled,
+            Ok(false) => ResponseCode::AnnounceNotFound,
+            Err(err) => {
+                println!("cancel announcement failed, ex:{}", err);
+                ResponseCode::ServerError
+            }
+        };
+
+        let id = id.to_string();
+        let message = I18N.get_with_arg(response_code.to_i18n_key(), locale, "id", &id);
+        command_send_message(ctx, command, message).await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_profile(
+    db: &sqlx::sqlite::SqlitePool,
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+
+        let player = command
+            .data
+            .options
+            .iter()
+            .find(|opt| opt.name == "player")
+            .and_then(|opt| opt.value.as_str())
+            .map(|player| player.trim().to_string())
+            .filter(|player| !player.is_empty());
+
+        // 未指定玩家時查詢自己綁定的帳號
+        let username = match player {
+            Some(player) => player,
+            None => match find_user(db, &command.user.id.to_string()).await {
+                Ok(user) => user.username,
+                Err(err) => {
+                    command_send_message(ctx, command, I18N.get(err.to_i18n_key(), locale))
+                        .await?;
+                    return Ok(());
+                }
+            },
+        };
+
+        if !check_username_valid(&username) {
+            command_send_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::InvalidInput.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let pool = mysql_pool();
+        let ranks = get_player_ranks(pool, &username).await;
+        let changes = get_recent_score_changes(pool, &username, PROFILE_RECENT_CHANGES).await;
+
+        let (ranks, changes) = match (ranks, changes) {
+            (Ok(ranks), Ok(changes)) => (ranks, changes),
+            (Err(err), _) | (_, Err(err)) => {
+                println!("query player profile failed, ex:{}", err);
+                command_send_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+        if ranks.is_empty() {
+            let message = I18N.get_with_arg(
+                ResponseCode::ProfileNotFound.to_i18n_key(),
+                locale,
+                "username",
+                &username,
+            );
+            command_send_message(ctx, command, message).await?;
+            return Ok(());
+        }
+
+        let mut embed = CreateEmbed::new()
+            .title(I18N.get_with_arg("profile_title", locale, "username", &username))
+            .color(0x3498db);
+
+        for rank in &ranks {
+            let score = format!("{:.1}", rank.score);
+            let position = rank.position.to_string();
+            let args = vec![("score", score.as_str()), ("rank", position.as_str())];
+            embed = embed.field(
+                format!("{} ({})", rank.category, rank.server),
+                I18N.get_with_args("profile_rating", locale, &args),
+                true,
+            );
+        }
+
+        if !changes.is_empty() {
+            let recent = changes
+                .iter()
+                .map(|change| {
+                    format!(
+                        "`{:+.1}` → {:.1} ({}, {}) <t:{}:R>",
+                        change.mmr_delta,
+                        change.mmr_after,
+                        change.category,
+                        change.result_flag,
+                        change.created_at.and_utc().timestamp()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            embed = embed.field(I18N.get("profile_recent_changes", locale), recent, false);
+        }
+
+        command_send_embed(ctx, command, embed).await?;
+    }
+
+    Ok(())
+}
+
+async fn handle_leaderboard(
+    ctx: &Context,
+    interaction: &Interaction,
+) -> serenity::Result<(), Error> {
+    if let Interaction::Command(command) = interaction {
+        let locale = command.locale.as_str();
+        let options = &command.data.options;
+
+        let get_str = |name: &str| {
+            options
+                .iter()
+                .find(|opt| opt.name == name)
+                .and_then(|opt| opt.value.as_str())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        let category = get_str("category");
+        let server = get_str("server");
 
-        report_channel_id.send_message(&ctx.http, message).await?;
+        let entries = match get_leaderboard(
+            mysql_pool(),
+            category.as_deref(),
+            server.as_deref(),
+            LEADERBOARD_SIZE,
+        )
+        .await
+        {
+            Ok(entries) => entries,
+            Err(err) => {
+                println!("query leaderboard failed, ex:{}", err);
+                command_send_message(
+                    ctx,
+                    command,
+                    I18N.get(ResponseCode::ServerError.to_i18n_key(), locale),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+        if entries.is_empty() {
+            command_send_message(
+                ctx,
+                command,
+                I18N.get(ResponseCode::LeaderboardEmpty.to_i18n_key(), locale),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let description = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                format!(
+                    "**{}.** {} — {:.1} ({}, {})",
+                    index + 1,
+                    entry.name,
+                    entry.score,
+                    entry.category,
+                    entry.server
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let filter = [category.as_deref(), server.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" / ");
+        let mut title = I18N.get("leaderboard_title", locale);
+        if !filter.is_empty() {
+            title = format!("{} - {}", title, filter);
+        }
+
+        let embed = CreateEmbed::new()
+            .title(title)
+            .description(description)
+            .color(0xf1c40f);
+
+        command_send_embed(ctx, command, embed).await?;
     }
 
     Ok(())
 }
 
+fn moderation_response(result: Result<ApiResult, ResponseCode>) -> ResponseCode {
+    match result {
+        Ok(ApiResult::Success(message)) => {
+            println!("moderation command succeeded, server replied: {}", message);
+            ResponseCode::ModerationSuccess
+        }
+        Ok(ApiResult::Failed(message)) => ResponseCode::ModerationRejected(message),
+        Ok(ApiResult::Unknown(message)) => ResponseCode::ModerationUnknown(message),
+        Ok(ApiResult::Timeout) => ResponseCode::ModerationTimeout,
+        Ok(ApiResult::Error) => ResponseCode::ModerationFailed,
+        Err(err) => err,
+    }
+}
+
+fn moderation_message(
+    response_code: &ResponseCode,
+    cmd: CommandType,
+    target: &str,
+    locale: &str,
+) -> String {
+    let server_message = match response_code {
+        ResponseCode::ModerationRejected(message) | ResponseCode::ModerationUnknown(message) => {
+            message.as_str()
+        }
+        _ => "",
+    };
+    let args = vec![
+        ("command", cmd.as_str()),
+        ("target", target),
+        ("message", server_message),
+    ];
+    I18N.get_with_args(response_code.to_i18n_key(), locale, &args)
+}
+
+fn has_moderator_role(member: Option<&Member>) -> bool {
+    if CONFIG.discord_moderator_role_id == 0 {
+        return false;
+    }
+    let moderator_role = RoleId::new(CONFIG.discord_moderator_role_id);
+    member.is_some_and(|member| member.roles.contains(&moderator_role))
+}
+
+async fn component_send_message(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    message: String,
+) -> serenity::Result<()> {
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(message)
+                    .ephemeral(true),
+            ),
+        )
+        .await
+}
+
 async fn command_send_message(
     ctx: &Context,
     command: &CommandInteraction,
@@ -295,6 +1235,40 @@ async fn command_send_message(
         .await
 }
 
+async fn command_defer(ctx: &Context, command: &CommandInteraction) -> serenity::Result<()> {
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(true)),
+        )
+        .await
+}
+
+/// Replaces the "thinking" placeholder left by [`command_defer`].
+async fn command_edit_message(
+    ctx: &Context,
+    command: &CommandInteraction,
+    message: String,
+) -> serenity::Result<()> {
+    command
+        .edit_response(&ctx.http, EditInteractionResponse::new().content(message))
+        .await?;
+    Ok(())
+}
+
+async fn command_send_embed(
+    ctx: &Context,
+    command: &CommandInteraction,
+    embed: CreateEmbed,
+) -> serenity::Result<()> {
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(CreateInteractionResponseMessage::new().embed(embed)),
+        )
+        .await
+}
+
 fn create_random_password() -> Option<String> {
     let mut rng = rand::rng();
     let random_number: u32 = rng.random_range(0..100_000_000);
@@ -425,8 +1399,16 @@ async fn change_password(
         .await?;
 
     match resp {
-        ApiResult::Success => Ok(user.username),
-        _ => Err(ResponseCode::ServerError),
+        ApiResult::Success(_) => Ok(user.username),
+        ApiResult::Failed(message) => {
+            println!("change password rejected by server, ex:{}", message);
+            Err(ResponseCode::ServerError)
+        }
+        ApiResult::Unknown(message) => {
+            println!("change password got an unexpected reply, ex:{}", message);
+            Err(ResponseCode::PasswordUnconfirmed)
+        }
+        ApiResult::Timeout | ApiResult::Error => Err(ResponseCode::ServerError),
     }
 }
 
@@ -439,6 +1421,15 @@ fn check_username_valid(user_id: &str) -> bool {
     regex.is_match(user_id)
 }
 
+fn check_ip_valid(ip: &str) -> bool {
+    if ip.is_empty() {
+        return false;
+    }
+
+    let regex = Regex::new(r"^[0-9*]{1,3}(\.[0-9*]{1,3}){3}$").unwrap();
+    regex.is_match(ip)
+}
+
 fn check_password_valid(password: &str) -> bool {
     if password.is_empty() {
         return false;
@@ -4,6 +4,7 @@ mod handler;
 mod interactions;
 mod query;
 mod response_code;
+mod scheduler;
 
 pub use bot::start_discord_bot;
 pub use response_code::*;
@@ -1,5 +1,11 @@
+use crate::model::announcement::Announcement;
+use crate::model::player::{MmrHistoryPoint, PlayerRank};
+use crate::model::report::{NewReport, Report, ReportStatus};
+use crate::model::score::LeaderboardEntry;
 use crate::model::user::User;
-use sqlx::{Row, SqlitePool};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{MySqlPool, Row, SqlitePool};
+use std::str::FromStr;
 
 pub async fn get_user_by_discord_id(
     pool: &SqlitePool,
@@ -40,3 +46,305 @@ pub async fn create_user(
 
     Ok(user)
 }
+
+pub async fn create_report(
+    pool: &SqlitePool,
+    report: &NewReport<'_>,
+) -> Result<Report, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO reports
+            (reporter_discord_id, reporter_name, reporter_locale, reported_username,
+             reason, comment, attachment_url, attachment_content_type)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(report.reporter_discord_id)
+    .bind(report.reporter_name)
+    .bind(report.reporter_locale)
+    .bind(report.reported_username)
+    .bind(report.reason)
+    .bind(report.comment)
+    .bind(report.attachment_url)
+    .bind(report.attachment_content_type)
+    .fetch_one(pool)
+    .await?;
+
+    report_from_row(&row)
+}
+
+pub async fn get_report_by_id(pool: &SqlitePool, id: i64) -> Result<Option<Report>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM reports WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    row.map(|row| report_from_row(&row)).transpose()
+}
+
+pub async fn set_report_message_id(
+    pool: &SqlitePool,
+    id: i64,
+    message_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE reports SET message_id = ? WHERE id = ?")
+        .bind(message_id)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Moves an open report to its final status. Returns `None` when the report
+/// was already resolved by someone else.
+pub async fn resolve_report(
+    pool: &SqlitePool,
+    id: i64,
+    status: ReportStatus,
+    ban_duration: Option<u32>,
+    moderator_name: &str,
+) -> Result<Option<Report>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        UPDATE reports
+        SET status = ?, ban_duration = ?, moderator_name = ?, resolved_at = unixepoch()
+        WHERE id = ? AND status = ?
+        RETURNING *
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(ban_duration)
+    .bind(moderator_name)
+    .bind(id)
+    .bind(ReportStatus::Open.as_str())
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(|row| report_from_row(&row)).transpose()
+}
+
+/// Puts a report claimed by [`resolve_report`] back to open, used when the
+/// ban it triggered failed.
+pub async fn reopen_report(pool: &SqlitePool, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE reports
+        SET status = ?, ban_duration = NULL, moderator_name = NULL, resolved_at = NULL
+        WHERE id = ? AND status = ?
+        "#,
+    )
+    .bind(ReportStatus::Open.as_str())
+    .bind(id)
+    .bind(ReportStatus::Accepted.as_str())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+fn report_from_row(row: &SqliteRow) -> Result<Report, sqlx::Error> {
+    let status: String = row.get("status");
+    let status = ReportStatus::from_str(&status).map_err(|e| sqlx::Error::Decode(e.into()))?;
+
+    Ok(Report {
+        id: row.get("id"),
+        reporter_discord_id: row.get("reporter_discord_id"),
+        reporter_name: row.get("reporter_name"),
+        reporter_locale: row.get("reporter_locale"),
+        reported_username: row.get("reported_username"),
+        reason: row.get("reason"),
+        comment: row.get("comment"),
+        attachment_url: row.get("attachment_url"),
+        attachment_content_type: row.get("attachment_content_type"),
+        status,
+        ban_duration: row.get("ban_duration"),
+        moderator_name: row.get("moderator_name"),
+        created_at: row.get("created_at"),
+    })
+}
+
+pub async fn create_announcement(
+    pool: &SqlitePool,
+    message: &str,
+    next_run_at: i64,
+    repeat_minutes: Option<u32>,
+    created_by: &str,
+) -> Result<Announcement, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO announcements (message, next_run_at, repeat_minutes, created_by)
+        VALUES (?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(message)
+    .bind(next_run_at)
+    .bind(repeat_minutes)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(announcement_from_row(&row))
+}
+
+pub async fn get_active_announcements(pool: &SqlitePool) -> Result<Vec<Announcement>, sqlx::Error> {
+    let rows = sqlx::query("SELECT * FROM announcements WHERE active = 1 ORDER BY next_run_at")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(announcement_from_row).collect())
+}
+
+pub async fn get_due_announcements(
+    pool: &SqlitePool,
+    now: i64,
+) -> Result<Vec<Announcement>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT * FROM announcements WHERE active = 1 AND next_run_at <= ? ORDER BY next_run_at",
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(announcement_from_row).collect())
+}
+
+/// Moves an announcement to its next run after a successful send.
+pub async fn reschedule_announcement(
+    pool: &SqlitePool,
+    id: i64,
+    next_run_at: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE announcements SET next_run_at = ?, failed_attempts = 0, last_error = NULL WHERE id = ?",
+    )
+    .bind(next_run_at)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Records a failed send and when to try again, `next_run_at` of `None`
+/// deactivates the announcement.
+pub async fn record_announcement_failure(
+    pool: &SqlitePool,
+    id: i64,
+    failed_attempts: u32,
+    error: &str,
+    next_run_at: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE announcements
+        SET failed_attempts = ?,
+            last_error = ?,
+            next_run_at = COALESCE(?, next_run_at),
+            active = ? IS NOT NULL
+        WHERE id = ?
+        "#,
+    )
+    .bind(failed_attempts)
+    .bind(error)
+    .bind(next_run_at)
+    .bind(next_run_at)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns `false` when no active announcement has this id.
+pub async fn deactivate_announcement(pool: &SqlitePool, id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE announcements SET active = 0 WHERE id = ? AND active = 1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+fn announcement_from_row(row: &SqliteRow) -> Announcement {
+    Announcement {
+        id: row.get("id"),
+        message: row.get("message"),
+        next_run_at: row.get("next_run_at"),
+        repeat_minutes: row.get("repeat_minutes"),
+        created_by: row.get("created_by"),
+        failed_attempts: row.get("failed_attempts"),
+        last_error: row.get("last_error"),
+    }
+}
+
+pub async fn get_player_ranks(
+    pool: &MySqlPool,
+    name: &str,
+) -> Result<Vec<PlayerRank>, sqlx::Error> {
+    sqlx::query_as::<_, PlayerRank>(
+        r#"
+        SELECT
+            s.category,
+            s.server,
+            s.score,
+            (SELECT COUNT(*) FROM scores o
+             WHERE o.category = s.category AND o.server = s.server AND o.score > s.score) + 1
+                AS position
+        FROM scores s
+        WHERE s.name = ?
+        ORDER BY s.score DESC
+        "#,
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_recent_score_changes(
+    pool: &MySqlPool,
+    name: &str,
+    limit: i64,
+) -> Result<Vec<MmrHistoryPoint>, sqlx::Error> {
+    sqlx::query_as::<_, MmrHistoryPoint>(
+        r#"
+        SELECT gameid, category, server, mmr_before, mmr_after, mmr_delta, result_flag,
+               performance_modifier, created_at
+        FROM score_change_logs
+        WHERE name = ? AND voided_at IS NULL
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(name)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_leaderboard(
+    pool: &MySqlPool,
+    category: Option<&str>,
+    server: Option<&str>,
+    limit: i64,
+) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+    sqlx::query_as::<_, LeaderboardEntry>(
+        r#"
+        SELECT name, category, server, score
+        FROM scores
+        WHERE (? IS NULL OR category = ?)
+          AND (? IS NULL OR server = ?)
+        ORDER BY score DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(category)
+    .bind(category)
+    .bind(server)
+    .bind(server)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
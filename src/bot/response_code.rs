@@ -8,8 +8,28 @@ pub enum ResponseCode {
     RegisterSuccess,
     NotRegistered,
     PasswordReset,
+    PasswordUnconfirmed,
     ReportInvalidInput,
     ReportSuccess,
+    PermissionDenied,
+    ModerationSuccess,
+    ModerationFailed,
+    ModerationRejected(String),
+    ModerationTimeout,
+    ModerationUnknown(String),
+    ReportNotFound,
+    ReportAlreadyResolved,
+    ReportAccepted,
+    ReportRejected,
+    AnnounceSent,
+    AnnounceFailed,
+    AnnounceScheduled,
+    AnnounceInvalidTime,
+    AnnounceListEmpty,
+    AnnounceCancelled,
+    AnnounceNotFound,
+    ProfileNotFound,
+    LeaderboardEmpty,
 }
 
 impl ResponseCode {
@@ -23,8 +43,28 @@ impl ResponseCode {
             ResponseCode::RegisterSuccess => "register_success",
             ResponseCode::NotRegistered => "not_registered",
             ResponseCode::PasswordReset => "password_reset",
+            ResponseCode::PasswordUnconfirmed => "password_unconfirmed",
             ResponseCode::ReportInvalidInput => "report_invalid_input",
             ResponseCode::ReportSuccess => "report_success",
+            ResponseCode::PermissionDenied => "permission_denied",
+            ResponseCode::ModerationSuccess => "moderation_success",
+            ResponseCode::ModerationFailed => "moderation_failed",
+            ResponseCode::ModerationRejected(_) => "moderation_rejected",
+            ResponseCode::ModerationTimeout => "moderation_timeout",
+            ResponseCode::ModerationUnknown(_) => "moderation_unknown",
+            ResponseCode::ReportNotFound => "report_not_found",
+            ResponseCode::ReportAlreadyResolved => "report_already_resolved",
+            ResponseCode::ReportAccepted => "report_accepted",
+            ResponseCode::ReportRejected => "report_rejected",
+            ResponseCode::AnnounceSent => "announce_sent",
+            ResponseCode::AnnounceFailed => "announce_failed",
+            ResponseCode::AnnounceScheduled => "announce_scheduled",
+            ResponseCode::AnnounceInvalidTime => "announce_invalid_time",
+            ResponseCode::AnnounceListEmpty => "announce_list_empty",
+            ResponseCode::AnnounceCancelled => "announce_cancelled",
+            ResponseCode::AnnounceNotFound => "announce_not_found",
+            ResponseCode::ProfileNotFound => "profile_not_found",
+            ResponseCode::LeaderboardEmpty => "leaderboard_empty",
         }
     }
 }
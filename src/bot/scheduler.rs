@@ -0,0 +1,113 @@
+use crate::bot::query::{
+    deactivate_announcement, get_due_announcements, record_announcement_failure,
+    reschedule_announcement,
+};
+use crate::model::announcement::Announcement;
+use crate::telnet::{self, ApiResult, Command};
+use chrono::Utc;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(15);
+/// First retry delay after a failed send, doubled on every further failure.
+const RETRY_INITIAL_DELAY_SECS: i64 = 60;
+/// Sends tried per run before a one-shot announcement is given up on or a
+/// repeating one waits for its next run.
+const MAX_SEND_ATTEMPTS: u32 = 5;
+
+pub fn start_announcement_scheduler(
+    db: SqlitePool,
+    telnet: Arc<telnet::ApiClient>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    tokio::spawn(async move {
+        let mut ticker = interval(SCHEDULER_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {},
+                _ = shutdown_rx.recv() => {
+                    info!("Announcement scheduler received shutdown signal");
+                    break;
+                }
+            }
+
+            if let Err(e) = send_due_announcements(&db, &telnet).await {
+                error!("Announcement scheduler error: {}", e);
+            }
+        }
+        info!("Announcement scheduler shutdown complete");
+    });
+}
+
+async fn send_due_announcements(
+    db: &SqlitePool,
+    telnet: &telnet::ApiClient,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now().timestamp();
+    let announcements = get_due_announcements(db, now).await?;
+
+    for announcement in announcements {
+        let result = telnet
+            .send_command(Command::Announce(announcement.message.clone()))
+            .await;
+
+        let error = match result {
+            Ok(ApiResult::Success(_)) => {
+                info!(
+                    "Scheduled announcement #{} sent (created by {})",
+                    announcement.id, announcement.created_by
+                );
+                match next_repeat_at(&announcement, now) {
+                    Some(next_run_at) => {
+                        reschedule_announcement(db, announcement.id, next_run_at).await?
+                    }
+                    None => {
+                        deactivate_announcement(db, announcement.id).await?;
+                    }
+                }
+                continue;
+            }
+            Ok(result) => format!("{:?}", result),
+            Err(e) => format!("{:?}", e),
+        };
+
+        // Keep the announcement and retry with backoff instead of dropping the run
+        let failed_attempts = announcement.failed_attempts + 1;
+        let (failed_attempts, next_run_at) = if failed_attempts < MAX_SEND_ATTEMPTS {
+            let delay = RETRY_INITIAL_DELAY_SECS << (failed_attempts - 1);
+            (failed_attempts, Some(now + delay))
+        } else {
+            (0, next_repeat_at(&announcement, now))
+        };
+
+        match next_run_at {
+            Some(next_run_at) => warn!(
+                "Scheduled announcement #{} failed: {}, next attempt at {}",
+                announcement.id, error, next_run_at
+            ),
+            None => error!(
+                "Scheduled announcement #{} failed {} times, giving up: {}",
+                announcement.id, MAX_SEND_ATTEMPTS, error
+            ),
+        }
+        record_announcement_failure(db, announcement.id, failed_attempts, &error, next_run_at)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Next regular run of a repeating announcement, `None` for one-shot ones.
+fn next_repeat_at(announcement: &Announcement, now: i64) -> Option<i64> {
+    let minutes = announcement.repeat_minutes.filter(|minutes| *minutes > 0)?;
+    // Skip runs missed while the bot was offline instead of replaying them
+    let period = minutes as i64 * 60;
+    let mut next_run_at = announcement.next_run_at + period;
+    if next_run_at <= now {
+        next_run_at += ((now - next_run_at) / period + 1) * period;
+    }
+    Some(next_run_at)
+}
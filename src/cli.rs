@@ -0,0 +1,94 @@
+use std::io::{self, Write};
+
+use tracing::info;
+
+use crate::database::mysql_pool;
+use crate::model::mmr::RecomputeReport;
+use crate::worker::mmr::{apply_shadow, replay_into_shadow, start_season, void_game};
+
+type CliResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+const USAGE: &str =
+    "usage: bn_manager mmr-recompute [--yes] | mmr-void <gameid> <reason> | season-start <name>";
+
+/// Runs a one-off maintenance command instead of starting the services.
+pub async fn run(command: &str, args: &[String]) -> CliResult {
+    match command {
+        "mmr-recompute" => mmr_recompute(args.iter().any(|arg| arg == "--yes")).await,
+        "mmr-void" => mmr_void(args).await,
+        "season-start" => season_start(args).await,
+        _ => Err(format!("unknown command: {}\n{}", command, USAGE).into()),
+    }
+}
+
+async fn mmr_recompute(skip_confirm: bool) -> CliResult {
+    let pool = mysql_pool();
+
+    info!("Replaying all games into shadow tables...");
+    let report = replay_into_shadow(pool).await?;
+    print_report(&report);
+
+    if !skip_confirm && !confirm("Apply the replayed ratings? [y/N] ")? {
+        println!("Aborted, live ratings are unchanged. Shadow tables are kept for inspection.");
+        return Ok(());
+    }
+
+    apply_shadow(pool).await?;
+    println!("Replayed ratings applied.");
+    Ok(())
+}
+
+async fn mmr_void(args: &[String]) -> CliResult {
+    let game_id = args
+        .first()
+        .and_then(|id| id.parse::<i32>().ok())
+        .ok_or(USAGE)?;
+    let reason = args[1..].join(" ");
+    if reason.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    let reverted = void_game(mysql_pool(), game_id, &reason).await?;
+    println!("Game {} voided, {} players reverted.", game_id, reverted);
+    Ok(())
+}
+
+async fn season_start(args: &[String]) -> CliResult {
+    let name = args.join(" ");
+    if name.is_empty() {
+        return Err(USAGE.into());
+    }
+
+    let season = start_season(mysql_pool(), &name).await?;
+    println!("Season {} \"{}\" started.", season.id, season.name);
+    Ok(())
+}
+
+fn print_report(report: &RecomputeReport) {
+    println!(
+        "Replayed {} games: {} changed, {} added, {} removed",
+        report.games_replayed, report.changed, report.added, report.removed
+    );
+    for diff in &report.diffs {
+        let format_score = |score: Option<f64>| match score {
+            Some(score) => format!("{:.1}", score),
+            None => "-".to_string(),
+        };
+        println!(
+            "  [{}/{}] {}: {} -> {}",
+            diff.category,
+            diff.server,
+            diff.name,
+            format_score(diff.current_score),
+            format_score(diff.replayed_score)
+        );
+    }
+}
+
+fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
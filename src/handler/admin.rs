@@ -0,0 +1,223 @@
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::database::mysql_pool;
+use crate::settings::CONFIG;
+use crate::bot::ResponseCode;
+use crate::telnet::{api_client, Command};
+use crate::worker::mmr::{
+    apply_shadow, start_recompute, start_season, trigger_processing, void_game, worker_status,
+    MmrAdminError,
+};
+
+#[derive(Deserialize)]
+pub struct VoidGameRequest {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct StartSeasonRequest {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct TelnetQueryRequest {
+    /// `who`, `finger`, `games`, `kick` or `raw`.
+    pub command: String,
+    /// Channel, account or raw console command, unused by `games`.
+    #[serde(default)]
+    pub target: String,
+}
+
+/// Reports the MMR worker's progress, backlog and last error.
+pub async fn mmr_worker_status(headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    match worker_status(mysql_pool()).await {
+        Ok(status) => Json(status).into_response(),
+        Err(err) => mmr_admin_error(err.into()),
+    }
+}
+
+/// Wakes the MMR worker to process the backlog right away.
+pub async fn process_mmr_now(headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    trigger_processing();
+    Json(json!({"triggered": true})).into_response()
+}
+
+/// Starts replaying every game into the shadow tables. The diff against
+/// `scores` is reported by `mmr_worker_status` once the replay finished.
+pub async fn recompute_mmr(headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    match start_recompute(mysql_pool()) {
+        Ok(()) => (StatusCode::ACCEPTED, Json(json!({"started": true}))).into_response(),
+        Err(err) => mmr_admin_error(err),
+    }
+}
+
+/// Swaps the replayed ratings in, run after reviewing the diff from `recompute_mmr`.
+pub async fn apply_mmr_recompute(headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    match apply_shadow(mysql_pool()).await {
+        Ok(()) => Json(json!({"applied": true})).into_response(),
+        Err(err) => mmr_admin_error(err),
+    }
+}
+
+/// Reverts a game's MMR changes and excludes it from future rating.
+pub async fn void_mmr_game(
+    headers: HeaderMap,
+    Path(game_id): Path<i32>,
+    Json(request): Json<VoidGameRequest>,
+) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    match void_game(mysql_pool(), game_id, request.reason.trim()).await {
+        Ok(reverted) => Json(json!({"gameid": game_id, "reverted": reverted})).into_response(),
+        Err(err) => mmr_admin_error(err),
+    }
+}
+
+/// Ends the running season with a snapshot of `scores` and soft resets the ratings.
+pub async fn start_mmr_season(
+    headers: HeaderMap,
+    Json(request): Json<StartSeasonRequest>,
+) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    let name = request.name.trim();
+    if name.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Season name is empty"})),
+        )
+            .into_response();
+    }
+
+    match start_season(mysql_pool(), name).await {
+        Ok(season) => Json(season).into_response(),
+        Err(err) => mmr_admin_error(err),
+    }
+}
+
+/// Reports the depth and latency of the telnet command queue.
+pub async fn telnet_status(headers: HeaderMap) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    match api_client() {
+        Some(client) => Json(client.metrics()).into_response(),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "Telnet client is not connected"})),
+        )
+            .into_response(),
+    }
+}
+
+/// Runs a console query over the bot's telnet connection and returns the reply lines.
+pub async fn telnet_query(
+    headers: HeaderMap,
+    Json(request): Json<TelnetQueryRequest>,
+) -> impl IntoResponse {
+    if let Err(err) = check_api_key(&headers) {
+        return err.into_response();
+    }
+
+    let target = request.target.trim().to_string();
+    let command = match (request.command.as_str(), target.is_empty()) {
+        ("games", _) => Command::Games,
+        (_, true) => return bad_request("Target is empty"),
+        ("who", false) => Command::Who(target),
+        ("finger", false) => Command::Finger(target),
+        ("kick", false) => Command::Kick(target),
+        ("raw", false) => Command::Raw(target),
+        _ => return bad_request("Unknown command"),
+    };
+
+    let Some(client) = api_client() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "Telnet client is not connected"})),
+        )
+            .into_response();
+    };
+
+    match client.query(command).await {
+        Ok(response) => Json(response).into_response(),
+        Err(ResponseCode::InvalidInput) => bad_request("Command must be a single line"),
+        Err(_) => (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({"error": "The server did not answer the command"})),
+        )
+            .into_response(),
+    }
+}
+
+fn bad_request(message: &str) -> Response {
+    (StatusCode::BAD_REQUEST, Json(json!({"error": message}))).into_response()
+}
+
+enum ApiKeyError {
+    Disabled,
+    Wrong,
+}
+
+impl IntoResponse for ApiKeyError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiKeyError::Disabled => (StatusCode::NOT_FOUND, "Admin api is disabled"),
+            ApiKeyError::Wrong => (StatusCode::UNAUTHORIZED, "Api key is wrong"),
+        };
+        (status, Json(json!({"error": message}))).into_response()
+    }
+}
+
+fn check_api_key(headers: &HeaderMap) -> Result<(), ApiKeyError> {
+    if CONFIG.admin_api_key.is_empty() {
+        return Err(ApiKeyError::Disabled);
+    }
+
+    let api_key = headers
+        .get("X-API-KEY")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    if api_key != CONFIG.admin_api_key {
+        println!("Admin api key is wrong");
+        return Err(ApiKeyError::Wrong);
+    }
+
+    Ok(())
+}
+
+fn mmr_admin_error(err: MmrAdminError) -> Response {
+    let status = match err {
+        MmrAdminError::Busy => StatusCode::CONFLICT,
+        MmrAdminError::NoShadowTables => StatusCode::BAD_REQUEST,
+        MmrAdminError::GameNotFound => StatusCode::NOT_FOUND,
+        MmrAdminError::AlreadyVoided => StatusCode::CONFLICT,
+        MmrAdminError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({"error": err.to_string()}))).into_response()
+}
@@ -1,60 +1,157 @@
-use axum::extract::Query;
-use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::Deserialize;
-use std::collections::HashMap;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::database::mysql_pool;
 use crate::model::game::Game;
-use crate::model::match_history::{MatchHistory, Servant, Team};
+use crate::model::match_history::{
+    MatchDetail, MatchHistory, Servant, ServantDetail, Team, TeamDetail,
+};
 use crate::model::pagination::{paginate, PaginationResult};
+use crate::settings::CONFIG;
+use crate::util::w3mmd::{self, PlayerVars, TeamInfo, W3mmdSchema};
+use crate::worker::rating::{rating_system, Rating, TeamRating};
+
+const DEFAULT_LIMIT: i64 = 10;
+const RESULT_FLAGS: [&str; 4] = ["winner", "loser", "drawer", "leaver"];
+const TOTAL_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Unfiltered game count and when it was taken.
+static TOTAL_CACHE: Mutex<Option<(Instant, i64)>> = Mutex::new(None);
 
 #[derive(Deserialize)]
 pub struct MatchHistoryQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Id of the last game of the previous page, replaces `offset` for stable paging.
+    pub cursor: Option<i32>,
+    /// Games the player took part in.
+    pub player: Option<String>,
+    /// w3mmd flag of `player`, or of any player when no player is given.
+    pub result: Option<String>,
+    pub servant: Option<String>,
+    /// Part of the map file name.
+    pub map: Option<String>,
+    /// First day included, `YYYY-MM-DD`.
+    pub from: Option<NaiveDate>,
+    /// Last day included, `YYYY-MM-DD`.
+    pub to: Option<NaiveDate>,
+    /// Minimum duration in seconds.
+    pub min_duration: Option<i32>,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug)]
 struct PlayerInfo {
     username: String,
     #[allow(dead_code)]
     pid: i32,
-    servant: Option<String>,
-    kills: Option<i32>,
-    deaths: Option<i32>,
-    assists: Option<i32>,
-    level: Option<i32>,
+    vars: PlayerVars,
+}
+
+/// Players and team infos of one game.
+type GameDetails = (Vec<PlayerInfo>, Vec<TeamInfo>);
+
+#[derive(Debug, sqlx::FromRow)]
+struct MmrChange {
+    name: String,
+    category: String,
+    mmr_before: f64,
+    mmr_after: f64,
+    mmr_delta: f64,
+    result_flag: String,
 }
 
 pub async fn get_match_histories(
+    headers: HeaderMap,
     Query(params): Query<MatchHistoryQuery>,
 ) -> impl IntoResponse {
-    let mut limit = params.limit.unwrap_or(10);
+    let api_key = headers
+        .get("X-API-KEY")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+    let max_limit = if CONFIG.is_api_client(api_key) {
+        CONFIG.api_clients.match_history_limit
+    } else {
+        DEFAULT_LIMIT
+    };
+
+    let mut limit = params.limit.unwrap_or(DEFAULT_LIMIT);
     if limit <= 0 {
         limit = 1;
     }
-    if limit > 10 {
-        limit = 10;
+    if limit > max_limit {
+        limit = max_limit;
     }
 
     let mut offset = params.offset.unwrap_or(0);
-    if offset < 0 {
+    if offset < 0 || params.cursor.is_some() {
         offset = 0;
     }
 
+    if let Some(result) = params.result.as_deref().filter(|r| !r.is_empty())
+        && !RESULT_FLAGS.contains(&result)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "result must be winner, loser, drawer or leaver"})),
+        )
+            .into_response();
+    }
+
     let pool = mysql_pool();
 
+    let cursor = match params.cursor {
+        Some(id) => {
+            match sqlx::query_scalar::<_, NaiveDateTime>("SELECT datetime FROM games WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+            {
+                Ok(Some(datetime)) => Some((datetime, id)),
+                Ok(None) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({"error": "cursor does not match any game"})),
+                    )
+                        .into_response();
+                }
+                Err(e) => return internal_error(e),
+            }
+        }
+        None => None,
+    };
+
     // Fetch games
-    let games = match sqlx::query_as::<_, Game>(
-        "SELECT id, map, datetime, duration FROM games ORDER BY datetime DESC LIMIT ? OFFSET ?",
-    )
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(pool)
-    .await
-    {
+    let mut games_query: QueryBuilder<MySql> = QueryBuilder::new(
+        r#"
+        SELECT g.id, g.map, g.datetime, g.duration, v.voided_at
+        FROM games g
+        LEFT JOIN game_mmr_voided v ON v.gameid = g.id
+        "#,
+    );
+    push_filters(&mut games_query, &params);
+    if let Some((datetime, id)) = cursor {
+        games_query
+            .push(" AND (g.datetime, g.id) < (")
+            .push_bind(datetime)
+            .push(", ")
+            .push_bind(id)
+            .push(")");
+    }
+    games_query
+        .push(" ORDER BY g.datetime DESC, g.id DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let games = match games_query.build_query_as::<Game>().fetch_all(pool).await {
         Ok(g) => g,
         Err(e) => {
             return (
@@ -67,59 +164,19 @@ pub async fn get_match_histories(
 
     let mut match_histories = Vec::new();
 
-    for game in &games {
-        // Fetch player info with LEFT JOINs
-        let player_infos = match sqlx::query_as::<_, PlayerInfo>(
-            r#"
-            SELECT
-                wp.name AS username,
-                wp.pid,
-                v_servant.value_string AS servant,
-                v_kills.value_int AS kills,
-                v_deaths.value_int AS deaths,
-                v_assists.value_int AS assists,
-                v_level.value_int AS level
-            FROM w3mmdplayers wp
-            LEFT JOIN w3mmdvars v_servant ON v_servant.gameid = wp.gameid AND v_servant.pid = wp.pid AND v_servant.varname = 'servant'
-            LEFT JOIN w3mmdvars v_kills   ON v_kills.gameid   = wp.gameid AND v_kills.pid   = wp.pid AND v_kills.varname   = 'kills'
-            LEFT JOIN w3mmdvars v_deaths  ON v_deaths.gameid  = wp.gameid AND v_deaths.pid  = wp.pid AND v_deaths.varname  = 'deaths'
-            LEFT JOIN w3mmdvars v_assists ON v_assists.gameid = wp.gameid AND v_assists.pid = wp.pid AND v_assists.varname = 'assists'
-            LEFT JOIN w3mmdvars v_level   ON v_level.gameid   = wp.gameid AND v_level.pid   = wp.pid AND v_level.varname   = 'level'
-            WHERE wp.gameid = ?
-            "#,
-        )
-        .bind(game.id)
-        .fetch_all(pool)
-        .await
-        {
-            Ok(p) => p,
-            Err(e) => {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": e.to_string()})),
-                )
-                    .into_response();
-            }
-        };
-
-        // Fetch team info
-        let team_infos = match sqlx::query_scalar::<_, Option<String>>(
-            "SELECT value_string FROM w3mmdvars WHERE gameid = ? AND varname = 'team_info'",
-        )
-        .bind(game.id)
-        .fetch_all(pool)
-        .await
-        {
-            Ok(t) => t,
-            Err(e) => {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": e.to_string()})),
-                )
-                    .into_response();
-            }
-        };
+    let mut details = match fetch_game_details(pool, &games).await {
+        Ok(d) => d,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response();
+        }
+    };
 
+    for game in &games {
+        let (player_infos, team_infos) = details.remove(&game.id).unwrap_or_default();
         let teams = analyse(&team_infos, &player_infos);
 
         match_histories.push(MatchHistory {
@@ -127,15 +184,12 @@ pub async fn get_match_histories(
             map: game.map.clone(),
             datetime: game.datetime,
             duration: game.duration,
+            voided: game.voided_at.is_some(),
             teams,
         });
     }
 
-    // Total count
-    let total = match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM games")
-        .fetch_one(pool)
-        .await
-    {
+    let total = match count_games(pool, &params).await {
         Ok(t) => t,
         Err(e) => {
             return (
@@ -146,7 +200,17 @@ pub async fn get_match_histories(
         }
     };
 
+    // With a cursor the offset is the number of games up to it, so the page
+    // numbers match offset paging
+    if let Some(cursor) = cursor {
+        offset = match count_games_through(pool, &params, cursor).await {
+            Ok(count) => count,
+            Err(e) => return internal_error(e),
+        };
+    }
+
     let (pages, current_page, has_next) = paginate(total, limit, offset);
+    let next_cursor = match_histories.last().map(|m| m.id);
 
     Json(PaginationResult {
         total,
@@ -155,92 +219,384 @@ pub async fn get_match_histories(
         page: current_page,
         pages,
         has_next,
+        next_cursor,
         data: match_histories,
     })
     .into_response()
 }
 
-fn analyse(team_infos: &[Option<String>], player_infos: &[PlayerInfo]) -> Vec<Team> {
-    let mut team_map: HashMap<i32, Team> = HashMap::new();
+/// Appends the `WHERE` clause shared by the page and count queries.
+fn push_filters(builder: &mut QueryBuilder<'_, MySql>, params: &MatchHistoryQuery) {
+    builder.push(" WHERE 1=1");
 
-    for team_info in team_infos {
-        let (index, team_name, score) = match team_info_split(team_info) {
-            Some(v) => v,
-            None => continue,
+    let player = params.player.clone().filter(|p| !p.is_empty());
+    let result = params.result.clone().filter(|r| !r.is_empty());
+    if player.is_some() || result.is_some() {
+        builder.push(" AND EXISTS (SELECT 1 FROM w3mmdplayers wp WHERE wp.gameid = g.id");
+        if let Some(player) = player {
+            builder.push(" AND wp.name = ").push_bind(player);
+        }
+        if let Some(result) = result {
+            builder.push(" AND wp.flag = ").push_bind(result);
+        }
+        builder.push(")");
+    }
+    if let Some(servant) = params.servant.as_ref().filter(|s| !s.is_empty()) {
+        // Any field of the servant var equals the name, in each schema's layout
+        let layouts: BTreeSet<(&str, &str)> = w3mmd::schemas()
+            .map(|schema| (schema.servant_var.as_str(), schema.separator.as_str()))
+            .collect();
+        builder.push(" AND EXISTS (SELECT 1 FROM w3mmdvars ws WHERE ws.gameid = g.id AND (");
+        for (i, (varname, separator)) in layouts.into_iter().enumerate() {
+            if i > 0 {
+                builder.push(" OR ");
+            }
+            builder
+                .push("(ws.varname = ")
+                .push_bind(varname)
+                .push(" AND CONCAT(")
+                .push_bind(separator)
+                .push(", TRIM(BOTH '\"' FROM ws.value_string), ")
+                .push_bind(separator)
+                .push(") LIKE ")
+                .push_bind(format!(
+                    "%{}{}{}%",
+                    escape_like(separator),
+                    escape_like(servant),
+                    escape_like(separator)
+                ))
+                .push(r" ESCAPE '\\')");
+        }
+        builder.push("))");
+    }
+    if let Some(map) = params.map.as_ref().filter(|m| !m.is_empty()) {
+        builder
+            .push(" AND g.map LIKE ")
+            .push_bind(format!("%{}%", escape_like(map)))
+            .push(r" ESCAPE '\\'");
+    }
+    if let Some(from) = params.from {
+        builder.push(" AND g.datetime >= ").push_bind(from);
+    }
+    if let Some(to) = params.to {
+        builder.push(" AND g.datetime < ").push_bind(to).push(" + INTERVAL 1 DAY");
+    }
+    if let Some(min_duration) = params.min_duration {
+        builder.push(" AND g.duration >= ").push_bind(min_duration);
+    }
+}
+
+/// Escapes the `LIKE` wildcards in user input, for patterns with `ESCAPE '\\'`.
+pub(crate) fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Returns a single game with each player's rating change and the expected win chances.
+pub async fn get_match_history(Path(game_id): Path<i32>) -> impl IntoResponse {
+    let pool = mysql_pool();
+
+    let game = match sqlx::query_as::<_, Game>(
+        r#"
+        SELECT g.id, g.map, g.datetime, g.duration, v.voided_at
+        FROM games g
+        LEFT JOIN game_mmr_voided v ON v.gameid = g.id
+        WHERE g.id = ?
+        "#,
+    )
+    .bind(game_id)
+    .fetch_optional(pool)
+    .await
+    {
+        Ok(Some(g)) => g,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Match not found"})),
+            )
+                .into_response();
+        }
+        Err(e) => return internal_error(e),
+    };
+
+    let details = fetch_game_details(pool, std::slice::from_ref(&game)).await;
+    let (player_infos, team_infos) = match details {
+        Ok(mut d) => d.remove(&game.id).unwrap_or_default(),
+        Err(e) => return internal_error(e),
+    };
+
+    let changes = match sqlx::query_as::<_, MmrChange>(
+        r#"
+        SELECT name, category, mmr_before, mmr_after, mmr_delta, result_flag
+        FROM score_change_logs
+        WHERE gameid = ? AND voided_at IS NULL
+        "#,
+    )
+    .bind(game.id)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(c) => c,
+        Err(e) => return internal_error(e),
+    };
+
+    let teams = analyse(&team_infos, &player_infos);
+    Json(match_detail(game, teams, changes)).into_response()
+}
+
+/// Players and team infos of the given games, keyed by game id.
+///
+/// Uses one query for the players and one for the vars regardless of the
+/// number of games and reads the vars through each game's w3mmd schema.
+async fn fetch_game_details(
+    pool: &MySqlPool,
+    games: &[Game],
+) -> Result<HashMap<i32, GameDetails>, sqlx::Error> {
+    let mut details: HashMap<i32, GameDetails> = HashMap::new();
+    if games.is_empty() {
+        return Ok(details);
+    }
+    let game_ids: Vec<i32> = games.iter().map(|g| g.id).collect();
+
+    let mut players_query: QueryBuilder<MySql> = QueryBuilder::new(
+        "SELECT gameid, pid, name, category FROM w3mmdplayers WHERE gameid IN (",
+    );
+    let mut separated = players_query.separated(", ");
+    for id in &game_ids {
+        separated.push_bind(*id);
+    }
+    separated.push_unseparated(") ORDER BY gameid, pid");
+    let players = players_query
+        .build_query_as::<(i32, i32, String, String)>()
+        .fetch_all(pool)
+        .await?;
+
+    let vars = w3mmd::fetch_vars(pool, Some(&game_ids)).await?;
+
+    // A game's schema is selected by its map and the category its players report
+    let maps: HashMap<i32, &str> = games.iter().map(|g| (g.id, g.map.as_str())).collect();
+    let mut schemas: HashMap<i32, &W3mmdSchema> = HashMap::new();
+    let mut player_index: HashMap<(i32, i32), usize> = HashMap::new();
+    for (gameid, pid, name, category) in players {
+        schemas
+            .entry(gameid)
+            .or_insert_with(|| w3mmd::schema_for(maps[&gameid], &category));
+        let (player_infos, _) = details.entry(gameid).or_default();
+        player_index.insert((gameid, pid), player_infos.len());
+        player_infos.push(PlayerInfo {
+            username: name,
+            pid,
+            vars: PlayerVars::default(),
+        });
+    }
+
+    for var in vars {
+        let schema = *schemas
+            .entry(var.gameid)
+            .or_insert_with(|| w3mmd::schema_for(maps[&var.gameid], ""));
+        let (player_infos, team_infos) = details.entry(var.gameid).or_default();
+        if schema.is_team_info(&var) {
+            let team_info = var.value_string.as_deref().and_then(|raw| schema.parse_team_info(raw));
+            team_infos.extend(team_info);
+            continue;
+        }
+
+        let Some(&index) = player_index.get(&(var.gameid, var.pid)) else {
+            continue;
         };
+        schema.apply(&var, &mut player_infos[index].vars);
+    }
+
+    Ok(details)
+}
+
+/// Number of games matching the filters from the newest one up to and including `cursor`.
+async fn count_games_through(
+    pool: &MySqlPool,
+    params: &MatchHistoryQuery,
+    (datetime, id): (NaiveDateTime, i32),
+) -> Result<i64, sqlx::Error> {
+    let mut count_query: QueryBuilder<MySql> = QueryBuilder::new("SELECT COUNT(*) FROM games g");
+    push_filters(&mut count_query, params);
+    count_query
+        .push(" AND (g.datetime, g.id) >= (")
+        .push_bind(datetime)
+        .push(", ")
+        .push_bind(id)
+        .push(")");
+    count_query.build_query_scalar::<i64>().fetch_one(pool).await
+}
 
-        if team_map.contains_key(&index) {
-            tracing::warn!("team index duplicate: {}", index);
+/// Number of games matching the filters, the unfiltered total is cached.
+async fn count_games(pool: &MySqlPool, params: &MatchHistoryQuery) -> Result<i64, sqlx::Error> {
+    let filtered = params.player.as_ref().is_some_and(|p| !p.is_empty())
+        || params.result.as_ref().is_some_and(|r| !r.is_empty())
+        || params.servant.as_ref().is_some_and(|s| !s.is_empty())
+        || params.map.as_ref().is_some_and(|m| !m.is_empty())
+        || params.from.is_some()
+        || params.to.is_some()
+        || params.min_duration.is_some();
+
+    if !filtered
+        && let Some((counted_at, total)) = TOTAL_CACHE.lock().ok().and_then(|cache| *cache)
+        && counted_at.elapsed() < TOTAL_CACHE_TTL
+    {
+        return Ok(total);
+    }
+
+    let mut count_query: QueryBuilder<MySql> = QueryBuilder::new("SELECT COUNT(*) FROM games g");
+    push_filters(&mut count_query, params);
+    let total = count_query.build_query_scalar::<i64>().fetch_one(pool).await?;
+
+    if !filtered && let Ok(mut cache) = TOTAL_CACHE.lock() {
+        *cache = Some((Instant::now(), total));
+    }
+    Ok(total)
+}
+
+fn match_detail(game: Game, teams: Vec<Team>, changes: Vec<MmrChange>) -> MatchDetail {
+    let category = changes.first().map(|c| c.category.clone());
+    let changes: HashMap<String, MmrChange> =
+        changes.into_iter().map(|c| (c.name.clone(), c)).collect();
+
+    // Ratings before the game, deviations are not logged so only the averages count
+    let team_ratings: HashMap<i32, TeamRating> = teams
+        .iter()
+        .map(|team| {
+            let mut rating = TeamRating::default();
+            for servant in &team.servants {
+                if let Some(change) = changes.get(&servant.user_name) {
+                    rating.add(&Rating {
+                        mmr: change.mmr_before,
+                        deviation: None,
+                        volatility: None,
+                    });
+                }
+            }
+            (team.index, rating)
+        })
+        .collect();
+
+    let system = category.as_deref().map(rating_system);
+    let mut teams: Vec<TeamDetail> = teams
+        .into_iter()
+        .map(|team| {
+            let own = team_ratings[&team.index];
+            let mut opponents = TeamRating::default();
+            for (_, other) in team_ratings.iter().filter(|&(&index, _)| index != team.index) {
+                opponents.merge(other);
+            }
+            let rated = own.size > 0 && opponents.size > 0;
+
+            TeamDetail {
+                index: team.index,
+                name: team.name,
+                score: team.score,
+                avg_mmr: (own.size > 0).then(|| own.avg_mmr()),
+                win_probability: system
+                    .filter(|_| rated)
+                    .map(|system| system.win_probability(&own, &opponents)),
+                servants: team
+                    .servants
+                    .into_iter()
+                    .map(|servant| {
+                        let change = changes.get(&servant.user_name);
+                        ServantDetail {
+                            mmr_before: change.map(|c| c.mmr_before),
+                            mmr_after: change.map(|c| c.mmr_after),
+                            mmr_delta: change.map(|c| c.mmr_delta),
+                            result_flag: change.map(|c| c.result_flag.clone()),
+                            servant,
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    teams.sort_by_key(|team| team.index);
+
+    MatchDetail {
+        id: game.id,
+        map: game.map,
+        datetime: game.datetime,
+        duration: game.duration,
+        voided: game.voided_at.is_some(),
+        category,
+        teams,
+    }
+}
+
+fn internal_error(e: sqlx::Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+        .into_response()
+}
+
+fn analyse(team_infos: &[TeamInfo], player_infos: &[PlayerInfo]) -> Vec<Team> {
+    let mut team_map: HashMap<i32, Team> = HashMap::new();
+
+    for team_info in team_infos {
+        if team_map.contains_key(&team_info.index) {
+            tracing::warn!("team index duplicate: {}", team_info.index);
             return Vec::new();
         }
 
         team_map.insert(
-            index,
+            team_info.index,
             Team {
-                index,
-                name: team_name,
-                score,
+                index: team_info.index,
+                name: team_info.name.clone(),
+                score: team_info.score,
                 servants: Vec::new(),
             },
         );
     }
 
     for player in player_infos {
-        let servant_str = match &player.servant {
-            Some(s) => s,
-            None => return Vec::new(),
-        };
+        if player.vars.servant_raw.is_none() {
+            return Vec::new();
+        }
 
-        let (team_index, servant_name) = match player_info_split(servant_str) {
-            Some(v) => v,
-            None => continue,
+        let (Some(team_index), Some(servant_name)) = (player.vars.team, &player.vars.servant)
+        else {
+            continue;
         };
 
-        let team = match team_map.get_mut(&team_index) {
-            Some(t) => t,
-            None => {
-                tracing::warn!("not found team index: {}", team_index);
-                continue;
+        // Maps without team infos only report each player's team index
+        let team = if team_infos.is_empty() {
+            team_map.entry(team_index).or_insert_with(|| Team {
+                index: team_index,
+                name: format!("Team {}", team_index),
+                score: 0,
+                servants: Vec::new(),
+            })
+        } else {
+            match team_map.get_mut(&team_index) {
+                Some(t) => t,
+                None => {
+                    tracing::warn!("not found team index: {}", team_index);
+                    continue;
+                }
             }
         };
 
         team.servants.push(Servant {
             user_name: player.username.clone(),
-            name: servant_name,
-            level: convert_point_int(player.level),
-            kills: convert_point_int(player.kills),
-            deaths: convert_point_int(player.deaths),
-            assists: convert_point_int(player.assists),
+            name: servant_name.clone(),
+            level: convert_point_int(player.vars.level),
+            kills: convert_point_int(player.vars.kills),
+            deaths: convert_point_int(player.vars.deaths),
+            assists: convert_point_int(player.vars.assists),
         });
     }
 
     team_map.into_values().collect()
 }
 
-fn team_info_split(team_info: &Option<String>) -> Option<(i32, String, i32)> {
-    let s = team_info.as_ref()?;
-    let cleaned = s.trim_matches('"');
-    let parts: Vec<&str> = cleaned.split(':').collect();
-    if parts.len() != 3 {
-        tracing::warn!("team info format invalid: {}", s);
-        return None;
-    }
-    let index = parts[0].parse::<i32>().ok()?;
-    let score = parts[2].parse::<i32>().ok()?;
-    Some((index, parts[1].to_string(), score))
-}
-
-fn player_info_split(servant: &str) -> Option<(i32, String)> {
-    let cleaned = servant.trim_matches('"');
-    let parts: Vec<&str> = cleaned.split(':').collect();
-    if parts.len() != 2 {
-        tracing::warn!("servant info format invalid: {}", servant);
-        return None;
-    }
-    let team_id = parts[0].parse::<i32>().ok()?;
-    Some((team_id, parts[1].to_string()))
-}
-
 fn convert_point_int(value: Option<i32>) -> i32 {
     value.unwrap_or(-1)
 }
@@ -1,4 +1,8 @@
 pub mod room;
 pub mod map;
 pub mod match_history;
-pub mod score;
\ No newline at end of file
+pub mod score;
+pub mod player;
+pub mod admin;
+pub mod season;
+pub mod stats;
\ No newline at end of file
@@ -0,0 +1,363 @@
+use axum::extract::{Path, Query};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::database::mysql_pool;
+use crate::model::player::{
+    HeadToHead, MmrHistoryPoint, PairRecord, PlayerProfile, PlayerRating, PlayerStats,
+    PlayerTeammates, TeammateStats,
+};
+use crate::util::w3mmd;
+use crate::worker::mmr;
+use crate::worker::rating::Outcome;
+
+#[derive(Deserialize)]
+pub struct PlayerQuery {
+    pub category: Option<String>,
+    pub server: Option<String>,
+    pub history_limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct PairQuery {
+    pub category: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TeammatesQuery {
+    pub category: Option<String>,
+    pub min_games: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// A game of the player, read through the game's w3mmd schema.
+#[derive(Debug, sqlx::FromRow)]
+struct PlayerGame {
+    gameid: i32,
+    pid: i32,
+    category: String,
+    flag: String,
+    map: String,
+}
+
+/// A game shared by the player and another player, with both team indexes.
+#[derive(Debug, sqlx::FromRow)]
+struct SharedGame {
+    gameid: i32,
+    pid: i32,
+    category: String,
+    flag: String,
+    map: String,
+    other_pid: i32,
+    other_name: String,
+    #[sqlx(skip)]
+    team: Option<i32>,
+    #[sqlx(skip)]
+    other_team: Option<i32>,
+}
+
+pub async fn get_player(
+    Path(name): Path<String>,
+    Query(params): Query<PlayerQuery>,
+) -> impl IntoResponse {
+    let mut history_limit = params.history_limit.unwrap_or(100);
+    if history_limit <= 0 {
+        history_limit = 100;
+    }
+    if history_limit > 500 {
+        history_limit = 500;
+    }
+
+    let category = params.category.filter(|c| !c.is_empty());
+    let server = params.server.filter(|s| !s.is_empty());
+    let pool = mysql_pool();
+
+    // Current MMR per category/server
+    let ratings = match sqlx::query_as::<_, PlayerRating>(
+        r#"
+        SELECT category, server, score
+        FROM scores
+        WHERE name = ?
+          AND (? IS NULL OR category = ?)
+          AND (? IS NULL OR server = ?)
+        ORDER BY category, server
+        "#,
+    )
+    .bind(&name)
+    .bind(&category)
+    .bind(&category)
+    .bind(&server)
+    .bind(&server)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => return internal_error(e),
+    };
+
+    // Win/loss and K/D/A aggregates per category
+    let stats = match fetch_player_stats(pool, &name, &category, &server).await {
+        Ok(s) => s,
+        Err(e) => return internal_error(e),
+    };
+
+    // Most recent MMR changes, returned oldest first for charting
+    let mut mmr_history = match sqlx::query_as::<_, MmrHistoryPoint>(
+        r#"
+        SELECT gameid, category, server, mmr_before, mmr_after, mmr_delta, result_flag,
+               performance_modifier, created_at
+        FROM score_change_logs
+        WHERE name = ? AND voided_at IS NULL
+          AND (? IS NULL OR category = ?)
+          AND (? IS NULL OR server = ?)
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&name)
+    .bind(&category)
+    .bind(&category)
+    .bind(&server)
+    .bind(&server)
+    .bind(history_limit)
+    .fetch_all(pool)
+    .await
+    {
+        Ok(h) => h,
+        Err(e) => return internal_error(e),
+    };
+    mmr_history.reverse();
+
+    if ratings.is_empty() && stats.is_empty() && mmr_history.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "player not found"})),
+        )
+            .into_response();
+    }
+
+    Json(PlayerProfile {
+        name,
+        ratings,
+        stats,
+        mmr_history,
+    })
+    .into_response()
+}
+
+/// How two players did together and against each other.
+pub async fn get_head_to_head(
+    Path((name, opponent)): Path<(String, String)>,
+    Query(params): Query<PairQuery>,
+) -> impl IntoResponse {
+    let category = params.category.filter(|c| !c.is_empty());
+    let games = match fetch_shared_games(mysql_pool(), &name, Some(&opponent), &category).await {
+        Ok(g) => g,
+        Err(e) => return internal_error(e),
+    };
+
+    // Every row matched the opponent under MySQL's case-insensitive
+    // collation, so all of them are merged whatever the case of the stored name
+    let (together, against) = pair_records(&games, |_| String::new())
+        .into_values()
+        .next()
+        .map(|stats| (stats.together, stats.against))
+        .unwrap_or_default();
+
+    Json(HeadToHead {
+        player: name,
+        opponent,
+        together,
+        against,
+    })
+    .into_response()
+}
+
+/// Everyone the player shared a game with, most games together first.
+pub async fn get_teammates(
+    Path(name): Path<String>,
+    Query(params): Query<TeammatesQuery>,
+) -> impl IntoResponse {
+    let min_games = params.min_games.unwrap_or(1).max(1);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let category = params.category.filter(|c| !c.is_empty());
+
+    let games = match fetch_shared_games(mysql_pool(), &name, None, &category).await {
+        Ok(g) => g,
+        Err(e) => return internal_error(e),
+    };
+
+    // Account names are case-insensitive, "Saber" and "saber" are one player
+    let mut teammates: Vec<TeammateStats> = pair_records(&games, str::to_lowercase)
+        .into_values()
+        .filter(|stats| stats.together.games >= min_games)
+        .collect();
+    teammates.sort_by(|a, b| {
+        b.together
+            .games
+            .cmp(&a.together.games)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    teammates.truncate(limit);
+
+    Json(PlayerTeammates { name, teammates }).into_response()
+}
+
+async fn fetch_player_stats(
+    pool: &MySqlPool,
+    name: &str,
+    category: &Option<String>,
+    server: &Option<String>,
+) -> Result<Vec<PlayerStats>, sqlx::Error> {
+    let games = sqlx::query_as::<_, PlayerGame>(
+        r#"
+        SELECT wp.gameid, wp.pid, wp.category, wp.flag, g.map
+        FROM w3mmdplayers wp
+        JOIN games g ON g.id = wp.gameid
+        WHERE wp.name = ?
+          AND (? IS NULL OR wp.category = ?)
+          AND (? IS NULL OR g.server = ?)
+        "#,
+    )
+    .bind(name)
+    .bind(category)
+    .bind(category)
+    .bind(server)
+    .bind(server)
+    .fetch_all(pool)
+    .await?;
+
+    let schemas = games
+        .iter()
+        .map(|g| ((g.gameid, g.pid), w3mmd::schema_for(&g.map, &g.category)))
+        .collect();
+    let vars = w3mmd::fetch_player_vars(pool, &schemas).await?;
+
+    let mut stats: BTreeMap<&str, PlayerStats> = BTreeMap::new();
+    for game in &games {
+        let entry = stats
+            .entry(game.category.as_str())
+            .or_insert_with(|| PlayerStats {
+                category: game.category.clone(),
+                games: 0,
+                wins: 0,
+                losses: 0,
+                kills: 0,
+                deaths: 0,
+                assists: 0,
+            });
+        entry.games += 1;
+        match mmr::flag_outcome(&game.flag) {
+            Some(Outcome::Win) => entry.wins += 1,
+            Some(Outcome::Loss) => entry.losses += 1,
+            _ => {}
+        }
+        if let Some(vars) = vars.get(&(game.gameid, game.pid)) {
+            entry.kills += i64::from(vars.kills.unwrap_or(0));
+            entry.deaths += i64::from(vars.deaths.unwrap_or(0));
+            entry.assists += i64::from(vars.assists.unwrap_or(0));
+        }
+    }
+    Ok(stats.into_values().collect())
+}
+
+async fn fetch_shared_games(
+    pool: &MySqlPool,
+    name: &str,
+    other: Option<&str>,
+    category: &Option<String>,
+) -> Result<Vec<SharedGame>, sqlx::Error> {
+    let mut games = sqlx::query_as::<_, SharedGame>(
+        r#"
+        SELECT
+            a.gameid,
+            a.pid,
+            a.category,
+            a.flag,
+            g.map,
+            b.pid AS other_pid,
+            b.name AS other_name
+        FROM w3mmdplayers a
+        JOIN w3mmdplayers b ON b.gameid = a.gameid AND b.pid <> a.pid
+        JOIN games g ON g.id = a.gameid
+        WHERE a.name = ?
+          AND (? IS NULL OR b.name = ?)
+          AND (? IS NULL OR a.category = ?)
+        "#,
+    )
+    .bind(name)
+    .bind(other)
+    .bind(other)
+    .bind(category)
+    .bind(category)
+    .fetch_all(pool)
+    .await?;
+
+    // Teams come from each game's schema, a servant field or a var of its own
+    let mut schemas = HashMap::new();
+    for game in &games {
+        let schema = w3mmd::schema_for(&game.map, &game.category);
+        schemas.insert((game.gameid, game.pid), schema);
+        schemas.insert((game.gameid, game.other_pid), schema);
+    }
+    let vars = w3mmd::fetch_player_vars(pool, &schemas).await?;
+    for game in &mut games {
+        game.team = vars.get(&(game.gameid, game.pid)).and_then(|v| v.team);
+        game.other_team = vars.get(&(game.gameid, game.other_pid)).and_then(|v| v.team);
+    }
+    Ok(games)
+}
+
+/// Records per `key_of` the other player's name, split into same team and
+/// opposite teams. Each record is named after the first name seen for its key.
+fn pair_records(
+    games: &[SharedGame],
+    key_of: impl Fn(&str) -> String,
+) -> HashMap<String, TeammateStats> {
+    let mut records: HashMap<String, TeammateStats> = HashMap::new();
+    for game in games {
+        let (Some(team), Some(other_team)) = (game.team, game.other_team) else {
+            continue;
+        };
+
+        let stats = records
+            .entry(key_of(&game.other_name))
+            .or_insert_with(|| TeammateStats {
+                name: game.other_name.clone(),
+                together: PairRecord::default(),
+                against: PairRecord::default(),
+            });
+        let record = if team == other_team {
+            &mut stats.together
+        } else {
+            &mut stats.against
+        };
+        record.games += 1;
+        match mmr::flag_outcome(&game.flag) {
+            Some(Outcome::Win) => record.wins += 1,
+            Some(Outcome::Loss) => record.losses += 1,
+            _ => {}
+        }
+    }
+
+    for stats in records.values_mut() {
+        for record in [&mut stats.together, &mut stats.against] {
+            if record.games > 0 {
+                record.win_rate = record.wins as f64 / record.games as f64;
+            }
+        }
+    }
+    records
+}
+
+fn internal_error(e: sqlx::Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+        .into_response()
+}
@@ -1,51 +1,58 @@
-use std::collections::HashMap;
-use std::fs;
-use std::io::{BufRead, BufReader};
+use std::convert::Infallible;
 use axum::http::StatusCode;
-use axum::Json;
+use axum::{Extension, Json};
 use axum::response::IntoResponse;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use serde_json::json;
-use crate::model::room::RoomInfo;
-use crate::settings::CONFIG;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use crate::worker::room_watcher::{RoomEvent, RoomSnapshot, RoomWatcher};
 
-pub async fn room_info() -> impl IntoResponse {
-    let file = match fs::File::open(&CONFIG.bn_log_path) {
-        Ok(file) => file,
-        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "can't read log"})))
-    };
-
-    let mut hash_map: HashMap<u16, RoomInfo> = HashMap::new();
+pub async fn room_info(Extension(room_watcher): Extension<RoomWatcher>) -> impl IntoResponse {
+    match room_watcher.snapshot() {
+        Some(result) => (StatusCode::OK, Json(json!(result.rooms))),
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "can't read log"}))),
+    }
+}
 
-    let reader = BufReader::new(file);
+pub async fn server_status(Extension(room_watcher): Extension<RoomWatcher>) -> impl IntoResponse {
+    match room_watcher.snapshot() {
+        Some(result) => (StatusCode::OK, Json(json!(result.as_ref()))),
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "can't read log"}))),
+    }
+}
 
-    for line in reader.lines() {
-        let line = match line {
-            Ok(text) => text,
-            Err(_) => continue
-        };
+/// Server-Sent Events stream: a `snapshot` event with the full room list on connect,
+/// then `added` / `removed` / `updated` events as `server.dat` changes.
+pub async fn room_events(
+    Extension(room_watcher): Extension<RoomWatcher>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Subscribe before taking the snapshot so no change falls in between
+    let events = BroadcastStream::new(room_watcher.subscribe_events());
+    let initial = snapshot_event(room_watcher.snapshot());
 
-        let lines: Vec<&str> = line.split(',').collect();
-        match lines.len() {
-            3 => {
-                let _room_id: u16 = lines[1].parse().unwrap();
-                let room_info = RoomInfo {
-                    room_id: _room_id,
-                    room_name: lines[2].to_string(),
-                    player_count: 0,
-                };
-                hash_map.insert(_room_id, room_info);
-            }
-            5 => {
-                let _room_id: u16 = lines[4].parse().unwrap();
-                if let Some(room) = hash_map.get_mut(&_room_id) {
-                    room.player_count += 1;
-                }
-            }
-            _ => {}
+    let updates = events.filter_map(move |event| match event {
+        Ok(event) => {
+            let name = match &event {
+                RoomEvent::Added { .. } => "added",
+                RoomEvent::Removed { .. } => "removed",
+                RoomEvent::Updated { .. } => "updated",
+            };
+            Event::default().event(name).json_data(&event).ok()
         }
-    }
+        // A slow client missed events, resync it with a fresh snapshot
+        Err(_) => snapshot_event(room_watcher.snapshot()),
+    });
+
+    let stream = tokio_stream::iter(initial).chain(updates).map(Ok);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
 
-    let mut result: Vec<RoomInfo> = hash_map.into_values().collect();
-    result.sort_by(|a, b| a.room_id.cmp(&b.room_id));
-    return (StatusCode::OK, Json(json!(result)));
-}
\ No newline at end of file
+fn snapshot_event(snapshot: RoomSnapshot) -> Option<Event> {
+    let status = snapshot?;
+    Event::default()
+        .event("snapshot")
+        .json_data(&status.rooms)
+        .ok()
+}
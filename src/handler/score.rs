@@ -2,6 +2,7 @@ use axum::extract::Query;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
+use chrono::NaiveDateTime;
 use serde::Deserialize;
 
 use crate::database::mysql_pool;
@@ -13,6 +14,8 @@ pub struct ScoreQuery {
     pub category: Option<String>,
     pub server: Option<String>,
     pub name: Option<String>,
+    /// Ended seasons are read from their snapshot, the running season from `scores`.
+    pub season: Option<i32>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub limit: Option<i64>,
@@ -43,9 +46,43 @@ pub async fn get_scores(Query(params): Query<ScoreQuery>) -> impl IntoResponse {
         _ => "DESC",
     };
 
+    let pool = mysql_pool();
+    let mut table = "scores";
     let mut conditions = vec!["1=1".to_string()];
     let mut count_args: Vec<String> = Vec::new();
 
+    if let Some(season_id) = params.season {
+        let season = sqlx::query_scalar::<_, Option<NaiveDateTime>>(
+            "SELECT ended_at FROM seasons WHERE id = ?",
+        )
+        .bind(season_id)
+        .fetch_optional(pool)
+        .await;
+
+        match season {
+            Ok(Some(Some(_))) => {
+                table = "season_scores";
+                conditions.push("season_id = ?".to_string());
+                count_args.push(season_id.to_string());
+            }
+            Ok(Some(None)) => {}
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({"error": "Season not found"})),
+                )
+                    .into_response();
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": e.to_string()})),
+                )
+                    .into_response();
+            }
+        }
+    }
+
     if let Some(ref category) = params.category {
         if !category.is_empty() {
             conditions.push("category = ?".to_string());
@@ -66,10 +103,9 @@ pub async fn get_scores(Query(params): Query<ScoreQuery>) -> impl IntoResponse {
     }
 
     let where_clause = conditions.join(" AND ");
-    let pool = mysql_pool();
 
     // Count query
-    let count_query = format!("SELECT COUNT(*) FROM scores WHERE {}", where_clause);
+    let count_query = format!("SELECT COUNT(*) FROM {} WHERE {}", table, where_clause);
     let mut count_q = sqlx::query_scalar::<_, i64>(&count_query);
     for arg in &count_args {
         count_q = count_q.bind(arg);
@@ -88,8 +124,8 @@ pub async fn get_scores(Query(params): Query<ScoreQuery>) -> impl IntoResponse {
 
     // Data query
     let data_query = format!(
-        "SELECT id, name, score FROM scores WHERE {} ORDER BY {} {} LIMIT ? OFFSET ?",
-        where_clause, sort_by, sort_order
+        "SELECT id, name, score FROM {} WHERE {} ORDER BY {} {} LIMIT ? OFFSET ?",
+        table, where_clause, sort_by, sort_order
     );
     let mut data_q = sqlx::query_as::<_, Score>(&data_query);
     for arg in &count_args {
@@ -117,6 +153,7 @@ pub async fn get_scores(Query(params): Query<ScoreQuery>) -> impl IntoResponse {
         page: current_page,
         pages,
         has_next,
+        next_cursor: None,
         data: scores,
     })
     .into_response()
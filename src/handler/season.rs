@@ -0,0 +1,24 @@
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+
+use crate::database::mysql_pool;
+use crate::model::season::Season;
+
+/// Lists all seasons, newest first.
+pub async fn get_seasons() -> impl IntoResponse {
+    let seasons = sqlx::query_as::<_, Season>(
+        "SELECT id, name, started_at, ended_at FROM seasons ORDER BY started_at DESC, id DESC",
+    )
+    .fetch_all(mysql_pool())
+    .await;
+
+    match seasons {
+        Ok(seasons) => Json(seasons).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::extract::Query;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{Days, NaiveDate, Utc};
+use serde::Deserialize;
+use sqlx::{MySql, QueryBuilder};
+
+use crate::database::mysql_pool;
+use crate::model::stats::{ServantStats, ServantStatsReport};
+use crate::handler::match_history::escape_like;
+use crate::util::w3mmd::{self, PlayerVars};
+
+/// Days covered when the query has no `from`.
+const DEFAULT_RANGE_DAYS: u64 = 30;
+const STATS_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Reports by the `Debug` form of their query and when they were built.
+static STATS_CACHE: Mutex<BTreeMap<String, (Instant, ServantStatsReport)>> =
+    Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Deserialize)]
+pub struct ServantStatsQuery {
+    /// First day included, `YYYY-MM-DD`, defaults to 30 days before `to`.
+    pub from: Option<NaiveDate>,
+    /// Last day included, `YYYY-MM-DD`, defaults to today.
+    pub to: Option<NaiveDate>,
+    /// Part of the map file name.
+    pub map: Option<String>,
+    pub category: Option<String>,
+    /// MMR bracket on each player's rating before the game, limits the stats to rated games.
+    pub min_mmr: Option<f64>,
+    pub max_mmr: Option<f64>,
+}
+
+/// A player of a game matching the filters.
+#[derive(Debug, sqlx::FromRow)]
+struct PlayerRow {
+    gameid: i32,
+    pid: i32,
+    category: String,
+    flag: String,
+    map: String,
+}
+
+/// Totals of one servant over the players who picked it.
+#[derive(Debug, Default)]
+struct ServantTotals {
+    games: i64,
+    wins: i64,
+    kills_sum: i64,
+    kills_count: i64,
+    deaths_sum: i64,
+    deaths_count: i64,
+    assists_sum: i64,
+    assists_count: i64,
+    level_sum: i64,
+    level_count: i64,
+}
+
+impl ServantTotals {
+    fn add(&mut self, flag: &str, vars: &PlayerVars) {
+        self.games += 1;
+        if flag == "winner" {
+            self.wins += 1;
+        }
+        add_stat(&mut self.kills_sum, &mut self.kills_count, vars.kills);
+        add_stat(&mut self.deaths_sum, &mut self.deaths_count, vars.deaths);
+        add_stat(&mut self.assists_sum, &mut self.assists_count, vars.assists);
+        add_stat(&mut self.level_sum, &mut self.level_count, vars.level);
+    }
+}
+
+const PLAYER_ROWS_SELECT: &str = "SELECT p.gameid, p.pid, p.category, p.flag, g.map";
+
+/// Pick rate, win rate and average K/D/A and level per servant.
+///
+/// Each game's vars are read through its w3mmd schema, so the servants of
+/// every configured map are counted. The players of the whole range are read
+/// into memory, hence the default range and the cache.
+pub async fn get_servant_stats(Query(mut params): Query<ServantStatsQuery>) -> impl IntoResponse {
+    let to = *params.to.get_or_insert_with(|| Utc::now().date_naive());
+    let from = *params
+        .from
+        .get_or_insert_with(|| to - Days::new(DEFAULT_RANGE_DAYS));
+
+    let key = format!("{:?}", params);
+    if let Some((built_at, report)) = STATS_CACHE.lock().ok().and_then(|c| c.get(&key).cloned())
+        && built_at.elapsed() < STATS_CACHE_TTL
+    {
+        return Json(report).into_response();
+    }
+
+    let pool = mysql_pool();
+
+    let mut rows_query = filtered_query(PLAYER_ROWS_SELECT, &params);
+    let rows = match rows_query.build_query_as::<PlayerRow>().fetch_all(pool).await {
+        Ok(r) => r,
+        Err(e) => return internal_error(e),
+    };
+
+    let schemas = rows
+        .iter()
+        .map(|row| ((row.gameid, row.pid), w3mmd::schema_for(&row.map, &row.category)))
+        .collect();
+    let vars = match w3mmd::fetch_player_vars(pool, &schemas).await {
+        Ok(v) => v,
+        Err(e) => return internal_error(e),
+    };
+
+    // Only games with a servant pick count towards the pick rate
+    let mut games: HashSet<i32> = HashSet::new();
+    let mut totals: HashMap<String, ServantTotals> = HashMap::new();
+    for row in &rows {
+        let Some(vars) = vars.get(&(row.gameid, row.pid)) else {
+            continue;
+        };
+        let Some(servant) = vars.servant.as_ref() else {
+            continue;
+        };
+        games.insert(row.gameid);
+        totals.entry(servant.clone()).or_default().add(&row.flag, vars);
+    }
+    let total_games = games.len() as i64;
+
+    let mut servants: Vec<ServantStats> = totals
+        .into_iter()
+        .map(|(name, t)| ServantStats {
+            name,
+            games: t.games,
+            pick_rate: ratio(t.games, total_games).unwrap_or(0.0),
+            wins: t.wins,
+            win_rate: ratio(t.wins, t.games).unwrap_or(0.0),
+            avg_kills: ratio(t.kills_sum, t.kills_count),
+            avg_deaths: ratio(t.deaths_sum, t.deaths_count),
+            avg_assists: ratio(t.assists_sum, t.assists_count),
+            avg_level: ratio(t.level_sum, t.level_count),
+        })
+        .collect();
+    servants.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.name.cmp(&b.name)));
+
+    let report = ServantStatsReport {
+        from,
+        to,
+        total_games,
+        servants,
+    };
+    if let Ok(mut cache) = STATS_CACHE.lock() {
+        cache.retain(|_, (built_at, _)| built_at.elapsed() < STATS_CACHE_TTL);
+        cache.insert(key, (Instant::now(), report.clone()));
+    }
+    Json(report).into_response()
+}
+
+fn filtered_query(select: &str, params: &ServantStatsQuery) -> QueryBuilder<'static, MySql> {
+    let mut builder: QueryBuilder<MySql> = QueryBuilder::new(select);
+    builder.push(
+        r#"
+        FROM w3mmdplayers p
+        JOIN games g ON g.id = p.gameid
+        "#,
+    );
+    if params.min_mmr.is_some() || params.max_mmr.is_some() {
+        builder.push(" JOIN score_change_logs l ON l.gameid = p.gameid AND l.name = p.name AND l.voided_at IS NULL");
+    }
+
+    builder.push(" WHERE 1=1");
+    if let Some(from) = params.from {
+        builder.push(" AND g.datetime >= ").push_bind(from);
+    }
+    if let Some(to) = params.to {
+        builder.push(" AND g.datetime < ").push_bind(to).push(" + INTERVAL 1 DAY");
+    }
+    if let Some(map) = params.map.as_ref().filter(|m| !m.is_empty()) {
+        builder
+            .push(" AND g.map LIKE ")
+            .push_bind(format!("%{}%", escape_like(map)))
+            .push(r" ESCAPE '\\'");
+    }
+    if let Some(category) = params.category.as_ref().filter(|c| !c.is_empty()) {
+        builder.push(" AND p.category = ").push_bind(category.clone());
+    }
+    if let Some(min_mmr) = params.min_mmr {
+        builder.push(" AND l.mmr_before >= ").push_bind(min_mmr);
+    }
+    if let Some(max_mmr) = params.max_mmr {
+        builder.push(" AND l.mmr_before < ").push_bind(max_mmr);
+    }
+    builder
+}
+
+fn add_stat(sum: &mut i64, count: &mut i64, value: Option<i32>) {
+    if let Some(value) = value {
+        *sum += i64::from(value);
+        *count += 1;
+    }
+}
+
+fn internal_error(e: sqlx::Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+        .into_response()
+}
+
+fn ratio(part: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64)
+}
@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 use tokio::sync::broadcast;
 use tracing::{error, info, Level};
 mod bot;
+mod cli;
 mod database;
 mod handler;
 mod i18n;
@@ -24,10 +25,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Connecting to MySQL...");
     database::init_mysql_pool().await;
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(command) = args.first() {
+        return cli::run(command, &args[1..]).await;
+    }
+
     let (shutdown_tx, _) = broadcast::channel(1);
 
+    info!("Starting room watcher...");
+    let room_watcher = worker::room_watcher::start_room_watcher(shutdown_tx.subscribe());
+
     info!("Starting MMR worker...");
-    worker::mmr::start_mmr_worker(shutdown_tx.subscribe());
+    worker::mmr::start_mmr_worker(room_watcher.clone(), shutdown_tx.subscribe());
+
+    info!("Starting room feed worker...");
+    worker::room_feed::start_room_feed_worker(room_watcher.clone(), shutdown_tx.subscribe());
 
     let mut bot_shutdown_rx = shutdown_tx.subscribe();
 
@@ -35,7 +47,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let bot_async_task = bot::start_discord_bot(&mut bot_shutdown_rx);
     
     let axum_shutdown_tx = shutdown_tx.clone();
-    let app = routes::root::routes();
+    let app = routes::root::routes(room_watcher);
     info!("Starting Axum server on 0.0.0.0:3000...");
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
     let axum_server = axum::serve(
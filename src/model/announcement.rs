@@ -0,0 +1,10 @@
+pub struct Announcement {
+    pub id: i64,
+    pub message: String,
+    pub next_run_at: i64,
+    pub repeat_minutes: Option<u32>,
+    pub created_by: String,
+    /// Failed sends since the last successful one.
+    pub failed_attempts: u32,
+    pub last_error: Option<String>,
+}
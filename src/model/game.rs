@@ -6,4 +6,6 @@ pub struct Game {
     pub map: String,
     pub datetime: NaiveDateTime,
     pub duration: i32,
+    /// Set when the game was voided and does not count towards MMR.
+    pub voided_at: Option<NaiveDateTime>,
 }
@@ -7,6 +7,7 @@ pub struct MatchHistory {
     pub map: String,
     pub datetime: NaiveDateTime,
     pub duration: i32,
+    pub voided: bool,
     pub teams: Vec<Team>,
 }
 
@@ -33,3 +34,38 @@ pub struct Servant {
     #[serde(rename = "Assists")]
     pub assists: i32,
 }
+
+/// A single game with the rating movement of every player.
+#[derive(Debug, Serialize)]
+pub struct MatchDetail {
+    pub id: i32,
+    pub map: String,
+    pub datetime: NaiveDateTime,
+    pub duration: i32,
+    pub voided: bool,
+    /// Map category the game was rated in, `None` if it was not rated.
+    pub category: Option<String>,
+    pub teams: Vec<TeamDetail>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TeamDetail {
+    pub index: i32,
+    pub name: String,
+    pub score: i32,
+    /// Average rating of the rated members before the game.
+    pub avg_mmr: Option<f64>,
+    /// Expected chance of beating the other teams, computed from the ratings before the game.
+    pub win_probability: Option<f64>,
+    pub servants: Vec<ServantDetail>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServantDetail {
+    #[serde(flatten)]
+    pub servant: Servant,
+    pub mmr_before: Option<f64>,
+    pub mmr_after: Option<f64>,
+    pub mmr_delta: Option<f64>,
+    pub result_flag: Option<String>,
+}
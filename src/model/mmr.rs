@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+/// Outcome of replaying every game into the shadow MMR tables.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecomputeReport {
+    pub games_replayed: usize,
+    /// Players whose score differs between the live and replayed tables.
+    pub changed: i64,
+    /// Players only present after the replay.
+    pub added: i64,
+    /// Players only present in the live table.
+    pub removed: i64,
+    /// Largest differences first.
+    pub diffs: Vec<ScoreDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScoreDiff {
+    pub category: String,
+    pub name: String,
+    pub server: String,
+    pub current_score: Option<f64>,
+    pub replayed_score: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecomputeState {
+    #[default]
+    Idle,
+    Running,
+    Finished,
+    Failed,
+}
+
+/// Recompute started by `POST /api/admin/mmr/recompute`, which only starts it
+/// in the background.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecomputeJobStatus {
+    pub state: RecomputeState,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+    /// Diff of the finished replay, to be reviewed before applying it.
+    pub report: Option<RecomputeReport>,
+    pub error: Option<String>,
+}
+
+/// State of the background MMR worker, served by `/api/admin/mmr/status`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MmrWorkerStatus {
+    /// The worker or an admin operation currently holds the processing lock.
+    pub processing: bool,
+    pub last_processed_game_id: Option<i32>,
+    /// Games not yet rated.
+    pub backlog: i64,
+    /// Games handled since startup.
+    pub games_processed: u64,
+    /// Throughput of the latest run that found games.
+    pub games_per_second: Option<f64>,
+    pub last_run_at: Option<NaiveDateTime>,
+    pub next_run_at: Option<NaiveDateTime>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<NaiveDateTime>,
+    pub recompute: RecomputeJobStatus,
+}
@@ -5,3 +5,9 @@ pub mod game;
 pub mod match_history;
 pub mod pagination;
 pub mod score;
+pub mod report;
+pub mod announcement;
+pub mod player;
+pub mod mmr;
+pub mod season;
+pub mod stats;
@@ -8,6 +8,9 @@ pub struct PaginationResult<T: Serialize> {
     pub page: i64,
     pub pages: i64,
     pub has_next: bool,
+    /// Id of the last item, pass it as `cursor` to get the next page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<i32>,
     pub data: Vec<T>,
 }
 
@@ -0,0 +1,82 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub ratings: Vec<PlayerRating>,
+    pub stats: Vec<PlayerStats>,
+    pub mmr_history: Vec<MmrHistoryPoint>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PlayerRating {
+    pub category: String,
+    pub server: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PlayerStats {
+    pub category: String,
+    pub games: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MmrHistoryPoint {
+    pub gameid: i32,
+    pub category: String,
+    pub server: String,
+    pub mmr_before: f64,
+    pub mmr_after: f64,
+    pub mmr_delta: f64,
+    pub result_flag: String,
+    pub performance_modifier: f64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct PlayerRank {
+    pub category: String,
+    pub server: String,
+    pub score: f64,
+    pub position: i64,
+}
+
+/// Games shared by two players, counted from the first player's point of view.
+#[derive(Debug, Default, Serialize)]
+pub struct PairRecord {
+    pub games: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HeadToHead {
+    pub player: String,
+    pub opponent: String,
+    /// Games on the same team.
+    pub together: PairRecord,
+    /// Games on opposite teams.
+    pub against: PairRecord,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerTeammates {
+    pub name: String,
+    /// Most games together first.
+    pub teammates: Vec<TeammateStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TeammateStats {
+    pub name: String,
+    pub together: PairRecord,
+    pub against: PairRecord,
+}
@@ -0,0 +1,56 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportStatus {
+    Open,
+    Accepted,
+    Rejected,
+}
+
+impl ReportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportStatus::Open => "open",
+            ReportStatus::Accepted => "accepted",
+            ReportStatus::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for ReportStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(ReportStatus::Open),
+            "accepted" => Ok(ReportStatus::Accepted),
+            "rejected" => Ok(ReportStatus::Rejected),
+            _ => Err(format!("unknown report status: {}", s)),
+        }
+    }
+}
+
+pub struct Report {
+    pub id: i64,
+    pub reporter_discord_id: String,
+    pub reporter_name: String,
+    pub reporter_locale: String,
+    pub reported_username: String,
+    pub reason: String,
+    pub comment: String,
+    pub attachment_url: String,
+    pub attachment_content_type: Option<String>,
+    pub status: ReportStatus,
+    pub ban_duration: Option<u32>,
+    pub moderator_name: Option<String>,
+    pub created_at: u64,
+}
+
+pub struct NewReport<'a> {
+    pub reporter_discord_id: &'a str,
+    pub reporter_name: &'a str,
+    pub reporter_locale: &'a str,
+    pub reported_username: &'a str,
+    pub reason: &'a str,
+    pub comment: &'a str,
+    pub attachment_url: &'a str,
+    pub attachment_content_type: Option<&'a str>,
+}
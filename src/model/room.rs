@@ -1,8 +1,19 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct RoomInfo {
     pub room_id: u16,
     pub room_name: String,
     pub player_count: u8,
-}
\ No newline at end of file
+    pub players: Vec<String>,
+    pub game_type: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ServerStatus {
+    pub rooms: Vec<RoomInfo>,
+    /// Online users that aren't in a game.
+    pub lobby_users: Vec<String>,
+}
@@ -6,3 +6,11 @@ pub struct Score {
     pub name: String,
     pub score: f64,
 }
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub category: String,
+    pub server: String,
+    pub score: f64,
+}
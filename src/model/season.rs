@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Season {
+    pub id: i32,
+    pub name: String,
+    pub started_at: NaiveDateTime,
+    /// `None` for the running season.
+    pub ended_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServantStatsReport {
+    /// Days covered, the query's or the default range.
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Games matching the filters, the base of `pick_rate`.
+    pub total_games: i64,
+    pub servants: Vec<ServantStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServantStats {
+    pub name: String,
+    pub games: i64,
+    /// Share of games in which the servant was picked.
+    pub pick_rate: f64,
+    pub wins: i64,
+    pub win_rate: f64,
+    /// `None` when no game reported the stat.
+    pub avg_kills: Option<f64>,
+    pub avg_deaths: Option<f64>,
+    pub avg_assists: Option<f64>,
+    pub avg_level: Option<f64>,
+}
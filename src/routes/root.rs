@@ -9,17 +9,25 @@ use tower_http::cors::CorsLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::services::ServeDir;
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
+use crate::handler::admin::{
+    apply_mmr_recompute, mmr_worker_status, process_mmr_now, recompute_mmr, start_mmr_season,
+    telnet_query, telnet_status, void_mmr_game,
+};
 use crate::handler::map::*;
-use crate::handler::match_history::get_match_histories;
-use crate::handler::room::room_info;
+use crate::handler::match_history::{get_match_histories, get_match_history};
+use crate::handler::player::{get_head_to_head, get_player, get_teammates};
+use crate::handler::room::{room_events, room_info, server_status};
 use crate::handler::score::get_scores;
+use crate::handler::season::get_seasons;
+use crate::handler::stats::get_servant_stats;
 use crate::model::map::MapInfo;
 use crate::settings::CONFIG;
 use crate::util;
+use crate::worker::room_watcher::RoomWatcher;
 
 type Cache = Arc<Mutex<HashMap<String, MapInfo>>>;
 
-pub fn routes() -> Router {
+pub fn routes(room_watcher: RoomWatcher) -> Router {
     let hash_map =
         util::file::read_files_in_directory(Path::new(&CONFIG.map_path)).expect("can't read folder");
 
@@ -28,11 +36,30 @@ pub fn routes() -> Router {
     let cors = CorsLayer::permissive();
 
     let routes_apis = Router::new()
-        .route("/room_info", get(room_info));
+        .route("/room_info", get(room_info))
+        .route("/room_events", get(room_events))
+        .route("/server_status", get(server_status))
+        .layer(Extension(room_watcher));
 
     let routes_mmr = Router::new()
         .route("/api/scores", get(get_scores))
-        .route("/api/match_histories", get(get_match_histories));
+        .route("/api/match_histories", get(get_match_histories))
+        .route("/api/match_histories/{id}", get(get_match_history))
+        .route("/api/players/{name}", get(get_player))
+        .route("/api/players/{name}/vs/{opponent}", get(get_head_to_head))
+        .route("/api/players/{name}/teammates", get(get_teammates))
+        .route("/api/seasons", get(get_seasons))
+        .route("/api/stats/servants", get(get_servant_stats));
+
+    let routes_admin = Router::new()
+        .route("/api/admin/mmr/status", get(mmr_worker_status))
+        .route("/api/admin/mmr/process", post(process_mmr_now))
+        .route("/api/admin/mmr/recompute", post(recompute_mmr))
+        .route("/api/admin/mmr/recompute/apply", post(apply_mmr_recompute))
+        .route("/api/admin/mmr/games/{id}/void", post(void_mmr_game))
+        .route("/api/admin/seasons", post(start_mmr_season))
+        .route("/api/admin/telnet/status", get(telnet_status))
+        .route("/api/admin/telnet/query", post(telnet_query));
 
     let routes_maps = Router::new()
         .route("/get_maps", get(get_maps))
@@ -45,6 +72,7 @@ pub fn routes() -> Router {
         .fallback_service(ServeDir::new("static").append_index_html_on_directories(true))
         .merge(routes_apis)
         .merge(routes_mmr)
+        .merge(routes_admin)
         .merge(routes_maps)
         .layer(cors)
         .layer(TraceLayer::new_for_http().make_span_with(DefaultMakeSpan::default().include_headers(true)))
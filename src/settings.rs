@@ -1,9 +1,14 @@
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use tracing::error;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::util::w3mmd::{TeamSource, W3mmdSchema};
+use crate::worker::mmr::{MmrRules, MmrWorkerConfig, PerformanceRules, SeasonRules};
+use crate::worker::rating::RatingAlgorithm;
+
 #[derive(Debug, Deserialize)]
 pub struct Config {
     pub user_data_path: String,
@@ -15,6 +20,14 @@ pub struct Config {
     pub discord_token: String,
     pub discord_server_id: u64,
     pub discord_report_channel_id: u64,
+    /// Role allowed to use the moderation commands, 0 disables them.
+    #[serde(default)]
+    pub discord_moderator_role_id: u64,
+    /// Channel for the live room status embed, 0 disables the feed.
+    #[serde(default)]
+    pub discord_room_feed_channel_id: u64,
+    #[serde(default)]
+    pub room_feed_notify_new_games: bool,
     pub uid_offset: i32,
     pub bn_server: String,
     pub bn_username: String,
@@ -24,6 +37,46 @@ pub struct Config {
     pub mysql_host: String,
     pub mysql_port: u16,
     pub mysql_db_name: String,
+    /// `X-API-KEY` for the `/api/admin` endpoints, empty disables them.
+    #[serde(default)]
+    pub admin_api_key: String,
+    /// Rating algorithm for map categories not listed in `rating_systems`.
+    #[serde(default)]
+    pub default_rating_system: RatingAlgorithm,
+    #[serde(default)]
+    pub rating_systems: HashMap<String, RatingAlgorithm>,
+    #[serde(default)]
+    pub mmr_rules: MmrRules,
+    #[serde(default)]
+    pub mmr_performance: PerformanceRules,
+    #[serde(default)]
+    pub seasons: SeasonRules,
+    #[serde(default)]
+    pub mmr_worker: MmrWorkerConfig,
+    #[serde(default)]
+    pub api_clients: ApiClients,
+    /// w3mmd var layouts per map, games matching none use the default schema.
+    #[serde(default)]
+    pub w3mmd_schemas: Vec<W3mmdSchema>,
+}
+
+/// Trusted API clients, `[api_clients]` in settings.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ApiClients {
+    /// Keys accepted in the `X-API-KEY` header, `admin_api_key` is accepted too.
+    pub keys: Vec<String>,
+    /// Page size limit of `/api/match_histories` for these clients.
+    pub match_history_limit: i64,
+}
+
+impl Default for ApiClients {
+    fn default() -> Self {
+        ApiClients {
+            keys: Vec::new(),
+            match_history_limit: 100,
+        }
+    }
 }
 
 impl Config {
@@ -33,6 +86,19 @@ impl Config {
             self.mysql_user, self.mysql_password, self.mysql_host, self.mysql_port, self.mysql_db_name
         )
     }
+
+    /// Whether `key` belongs to an API client or is the admin key.
+    pub fn is_api_client(&self, key: &str) -> bool {
+        !key.is_empty()
+            && (key == self.admin_api_key || self.api_clients.keys.iter().any(|k| k == key))
+    }
+
+    pub fn rating_algorithm(&self, category: &str) -> RatingAlgorithm {
+        self.rating_systems
+            .get(category)
+            .copied()
+            .unwrap_or(self.default_rating_system)
+    }
 }
 
 pub static CONFIG: Lazy<Config> = Lazy::new(|| {
@@ -116,6 +182,66 @@ fn validate_config(config: &Config) -> bool {
         error!("MYSQL_DB_NAME is empty");
         return false;
     }
+    if config.mmr_rules.leaver_penalty < 0.0 {
+        error!("MMR_RULES.LEAVER_PENALTY cannot be negative");
+        return false;
+    }
+    if !(0.0..=1.0).contains(&config.mmr_rules.leaver_teammate_loss_factor) {
+        error!("MMR_RULES.LEAVER_TEAMMATE_LOSS_FACTOR must be between 0 and 1");
+        return false;
+    }
+    let performance = &config.mmr_performance;
+    if performance.kill_weight < 0.0
+        || performance.death_weight < 0.0
+        || performance.assist_weight < 0.0
+        || performance.level_weight < 0.0
+    {
+        error!("MMR_PERFORMANCE weights cannot be negative");
+        return false;
+    }
+    if !(0.0..1.0).contains(&performance.max_modifier) {
+        error!("MMR_PERFORMANCE.MAX_MODIFIER must be at least 0 and below 1");
+        return false;
+    }
+    let seasons = &config.seasons;
+    if !(0.0..=1.0).contains(&seasons.soft_reset_factor) {
+        error!("SEASONS.SOFT_RESET_FACTOR must be between 0 and 1");
+        return false;
+    }
+    if seasons.decay_per_day < 0.0 {
+        error!("SEASONS.DECAY_PER_DAY cannot be negative");
+        return false;
+    }
+    if config.mmr_worker.batch_size == 0 {
+        error!("MMR_WORKER.BATCH_SIZE must be at least 1");
+        return false;
+    }
+    if config.mmr_worker.min_interval_secs == 0
+        || config.mmr_worker.max_interval_secs < config.mmr_worker.min_interval_secs
+    {
+        error!("MMR_WORKER.MIN_INTERVAL_SECS must be between 1 and MAX_INTERVAL_SECS");
+        return false;
+    }
+    if config.api_clients.match_history_limit <= 0 {
+        error!("API_CLIENTS.MATCH_HISTORY_LIMIT must be at least 1");
+        return false;
+    }
+    for schema in &config.w3mmd_schemas {
+        if schema.map_pattern.as_ref().is_none_or(|p| p.is_empty())
+            && schema.category.as_ref().is_none_or(|c| c.is_empty())
+        {
+            error!("W3MMD_SCHEMAS entries need a MAP_PATTERN or a CATEGORY");
+            return false;
+        }
+        if schema.separator.is_empty() || schema.servant_var.is_empty() {
+            error!("W3MMD_SCHEMAS.SEPARATOR and SERVANT_VAR cannot be empty");
+            return false;
+        }
+        if matches!(&schema.team, TeamSource::Var { name } if name.is_empty()) {
+            error!("W3MMD_SCHEMAS.TEAM var name is empty");
+            return false;
+        }
+    }
     true
 }
 
@@ -1,18 +1,31 @@
 use crate::bot::ResponseCode;
 use rand::RngExt;
+use serde::Serialize;
 use std::fmt;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
-use tokio::sync::{oneshot, watch, Mutex as TokioMutex, Notify};
+use tokio::sync::{mpsc, oneshot, watch, Mutex as TokioMutex, Notify, OnceCell};
 use tokio::time::timeout;
 use tracing::{error, info, warn};
 
 const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
 const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
 const RECONNECT_COMMAND_WAIT: Duration = Duration::from_secs(15);
+const COMMAND_RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+const COMMAND_QUEUE_TIMEOUT: Duration = Duration::from_secs(10);
+const COMMAND_QUEUE_CAPACITY: usize = 64;
+const QUERY_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+const QUERY_IDLE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// PvPGN chat mode event codes of the server's own replies, and of the
+/// broadcast `/announce` echoes back to every user.
+const EID_BROADCAST: u16 = 1006;
+const EID_INFO: u16 = 1018;
+const EID_ERROR: u16 = 1019;
 
 #[derive(Debug)]
 pub enum Command {
@@ -21,6 +34,13 @@ pub enum Command {
     ChangePassword(String, String),
     Unban(String),
     UnIPBan(String),
+    Who(String),
+    Finger(String),
+    Games,
+    Announce(String),
+    Kick(String),
+    /// Any console command, sent verbatim.
+    Raw(String),
 }
 
 impl fmt::Display for Command {
@@ -31,17 +51,216 @@ impl fmt::Display for Command {
             Command::ChangePassword(u, p) => write!(f, "/chpass {} {}", u, p),
             Command::Unban(user) => write!(f, "/unlock {}", user),
             Command::UnIPBan(ip) => write!(f, "/ipban d {}", ip),
+            Command::Who(channel) => write!(f, "/who {}", channel),
+            Command::Finger(user) => write!(f, "/finger {}", user),
+            Command::Games => write!(f, "/games"),
+            Command::Announce(message) => write!(f, "/announce {}", message),
+            Command::Kick(user) => write!(f, "/kick {}", user),
+            Command::Raw(raw) => write!(f, "{}", raw),
         }
     }
 }
 
+impl Command {
+    fn response_timeout(&self) -> Duration {
+        if self.collects_response() {
+            QUERY_RESPONSE_TIMEOUT
+        } else {
+            COMMAND_RESPONSE_TIMEOUT
+        }
+    }
+
+    /// Queries have no single confirmation line, so their reply is collected
+    /// until the server goes quiet.
+    fn collects_response(&self) -> bool {
+        matches!(
+            self,
+            Command::Who(..)
+                | Command::Finger(..)
+                | Command::Games
+                | Command::Announce(..)
+                | Command::Kick(..)
+                | Command::Raw(..)
+        )
+    }
+
+    /// Whether `line` is the broadcast this command sends, `/announce` is
+    /// confirmed by its own echo rather than by a reply text.
+    fn is_echo(&self, line: &ResponseLine) -> bool {
+        match self {
+            Command::Announce(message) => {
+                line.kind == LineKind::Broadcast && line.text.contains(message.as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Substrings of the replies PvPGN's `handle_command.cpp` sends for each
+    /// admin command, e.g. `That user's account is now locked.` for `/lock`.
+    fn response_matcher(&self) -> ResponseMatcher {
+        match self {
+            Command::Ban(..) => ResponseMatcher {
+                success: &["is now locked"],
+                failure: &["invalid user", "does not exist"],
+            },
+            Command::IPBan(..) => ResponseMatcher {
+                success: &["banned permanently by", "banned for"],
+                failure: &["invalid ip", "already banned", "bad ip"],
+            },
+            Command::ChangePassword(..) => ResponseMatcher {
+                success: &["password for account"],
+                failure: &[
+                    "invalid user",
+                    "does not exist",
+                    "unable to set password",
+                    "only admins may change passwords",
+                ],
+            },
+            Command::Unban(..) => ResponseMatcher {
+                success: &["is now unlocked"],
+                failure: &["invalid user", "does not exist"],
+            },
+            Command::UnIPBan(..) => ResponseMatcher {
+                success: &["entry deleted", "deleted from"],
+                failure: &["no matching", "no entry", "not found"],
+            },
+            _ => ResponseMatcher {
+                success: &[],
+                failure: &[],
+            },
+        }
+    }
+}
+
+/// Replies every admin command can receive regardless of its own matcher.
+const COMMON_FAILURES: &[&str] = &[
+    "usage:",
+    "unknown command",
+    "you do not have",
+    "not allowed",
+];
+
+/// Case-insensitive substrings used to classify the server's reply lines to a command.
+struct ResponseMatcher {
+    success: &'static [&'static str],
+    failure: &'static [&'static str],
+}
+
+impl ResponseMatcher {
+    /// Only the server's INFO and ERROR replies are classified, an ERROR is a
+    /// failure whatever its text.
+    fn classify(&self, line: &ResponseLine) -> Option<ApiResult> {
+        let message = line.text.clone();
+        match line.kind {
+            LineKind::Error => return Some(ApiResult::Failed(message)),
+            LineKind::Broadcast | LineKind::Text => return None,
+            LineKind::Info => {}
+        }
+
+        let lower = message.to_lowercase();
+        if self
+            .failure
+            .iter()
+            .chain(COMMON_FAILURES)
+            .any(|p| lower.contains(p))
+        {
+            return Some(ApiResult::Failed(message));
+        }
+        if self.success.iter().any(|p| lower.contains(p)) {
+            return Some(ApiResult::Success(message));
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Info,
+    Error,
+    Broadcast,
+    /// A line without an event code.
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseLine {
+    pub kind: LineKind,
+    pub text: String,
+}
+
+impl ResponseLine {
+    /// Strips the numeric event code and quoting PvPGN adds in chat mode,
+    /// e.g. `1018 INFO "Password for account foo updated."`.
+    ///
+    /// Returns `None` for every other event, such as whispers and talk from
+    /// other users, which are never a reply to our command.
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let event = line
+            .split_once(' ')
+            .and_then(|(code, rest)| code.parse::<u16>().ok().map(|code| (code, rest)));
+        let Some((code, rest)) = event else {
+            return Some(ResponseLine {
+                kind: LineKind::Text,
+                text: line.trim_matches('"').to_string(),
+            });
+        };
+
+        let kind = match code {
+            EID_INFO => LineKind::Info,
+            EID_ERROR => LineKind::Error,
+            EID_BROADCAST => LineKind::Broadcast,
+            _ => return None,
+        };
+        // The event name, e.g. `INFO`, sits between the code and the text
+        let text = rest.split_once(' ').map_or("", |(_, text)| text);
+        Some(ResponseLine {
+            kind,
+            text: text.trim_matches('"').to_string(),
+        })
+    }
+}
+
+/// Full reply to a query, one entry per line the server sent.
+#[derive(Debug, Serialize)]
+pub struct QueryResponse {
+    pub command: String,
+    pub success: bool,
+    pub lines: Vec<ResponseLine>,
+}
+
 #[derive(Debug)]
 pub enum ApiResult {
-    Success,
+    /// The server confirmed the command, carrying its reply.
+    Success(String),
+    /// The server rejected the command, carrying its reply.
+    Failed(String),
+    /// The server replied, but with nothing the command's matcher knows.
+    Unknown(String),
+    /// The connection was lost or the command could not be written.
     Error,
+    /// The server sent nothing before the response timeout.
     Timeout,
 }
 
+/// What the dispatcher hands back to a caller: the classified result plus
+/// every reply line received while the command was active.
+#[derive(Debug)]
+struct CommandOutput {
+    result: ApiResult,
+    lines: Vec<ResponseLine>,
+}
+
+impl CommandOutput {
+    fn error() -> Self {
+        CommandOutput {
+            result: ApiResult::Error,
+            lines: Vec::new(),
+        }
+    }
+}
+
 enum ConnectionStatus {
     Connected { writer: OwnedWriteHalf },
     Disconnected,
@@ -50,12 +269,78 @@ enum ConnectionStatus {
 
 struct SharedState {
     status: ConnectionStatus,
-    current_sender: Option<oneshot::Sender<ApiResult>>,
+    /// Receives every line read while a command is in flight.
+    current_command: Option<mpsc::UnboundedSender<String>>,
+}
+
+struct QueuedCommand {
+    command: Command,
+    reply: oneshot::Sender<CommandOutput>,
+    enqueued_at: Instant,
+    response_timeout: Duration,
+}
+
+#[derive(Default)]
+struct QueueMetrics {
+    depth: AtomicUsize,
+    processed: AtomicU64,
+    timeouts: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+}
+
+impl QueueMetrics {
+    fn record(&self, latency: Duration, timed_out: bool) {
+        let latency_ms = latency.as_millis() as u64;
+        self.processed.fetch_add(1, Ordering::SeqCst);
+        self.total_latency_ms
+            .fetch_add(latency_ms, Ordering::SeqCst);
+        self.max_latency_ms.fetch_max(latency_ms, Ordering::SeqCst);
+        if timed_out {
+            self.timeouts.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn snapshot(&self) -> QueueMetricsSnapshot {
+        let processed = self.processed.load(Ordering::SeqCst);
+        let total_latency_ms = self.total_latency_ms.load(Ordering::SeqCst);
+        QueueMetricsSnapshot {
+            queue_depth: self.depth.load(Ordering::SeqCst),
+            processed,
+            timeouts: self.timeouts.load(Ordering::SeqCst),
+            avg_latency_ms: total_latency_ms.checked_div(processed).unwrap_or(0),
+            max_latency_ms: self.max_latency_ms.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// Queue depth and latency (time from enqueue to result) of the command queue.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueMetricsSnapshot {
+    pub queue_depth: usize,
+    pub processed: u64,
+    pub timeouts: u64,
+    pub avg_latency_ms: u64,
+    pub max_latency_ms: u64,
 }
 
 pub struct ApiClient {
-    shared: Arc<TokioMutex<SharedState>>,
-    connected: watch::Receiver<bool>,
+    queue: mpsc::Sender<QueuedCommand>,
+    metrics: Arc<QueueMetrics>,
+}
+
+static API_CLIENT: OnceCell<Arc<ApiClient>> = OnceCell::const_new();
+
+/// Shares the bot's client with the admin api.
+pub fn set_api_client(client: Arc<ApiClient>) {
+    if API_CLIENT.set(client).is_err() {
+        warn!("Telnet client already initialized");
+    }
+}
+
+/// The running client, `None` until the Discord bot has connected to the server.
+pub fn api_client() -> Option<&'static ApiClient> {
+    API_CLIENT.get().map(|client| client.as_ref())
 }
 
 async fn connect_and_login(
@@ -109,9 +394,12 @@ impl ApiClient {
 
         let shared = Arc::new(TokioMutex::new(SharedState {
             status: ConnectionStatus::Connected { writer },
-            current_sender: None,
+            current_command: None,
         }));
 
+        let (queue_tx, queue_rx) = mpsc::channel(COMMAND_QUEUE_CAPACITY);
+        let metrics = Arc::new(QueueMetrics::default());
+
         // Spawn reader loop
         let shared_clone = shared.clone();
         let reconnect_notify_clone = reconnect_notify.clone();
@@ -120,14 +408,20 @@ impl ApiClient {
             reader_loop(reader, shared_clone, reconnect_notify_clone, connected_tx_clone).await;
         });
 
-        // Spawn reconnection background task
+        // Spawn command dispatcher, the only task that writes commands
         let shared_clone = shared.clone();
+        let metrics_clone = metrics.clone();
+        tokio::spawn(async move {
+            dispatch_loop(queue_rx, shared_clone, connected_rx, metrics_clone).await;
+        });
+
+        // Spawn reconnection background task
         let server = server.to_string();
         let username = username.to_string();
         let password = password.to_string();
         tokio::spawn(async move {
             reconnect_task(
-                shared_clone,
+                shared,
                 reconnect_notify,
                 connected_tx,
                 server,
@@ -138,75 +432,235 @@ impl ApiClient {
         });
 
         Ok(ApiClient {
-            shared,
-            connected: connected_rx,
+            queue: queue_tx,
+            metrics,
         })
     }
 
+    /// Queues a command behind any in-flight ones and waits for its result.
     pub async fn send_command(&self, command: Command) -> Result<ApiResult, ResponseCode> {
-        // Check connection status, wait for reconnection if needed
-        {
-            let shared = self.shared.lock().await;
-            if !matches!(shared.status, ConnectionStatus::Connected { .. }) {
-                drop(shared);
-                let mut rx = self.connected.clone();
-                let wait_result = timeout(RECONNECT_COMMAND_WAIT, rx.wait_for(|&c| c)).await;
-                match wait_result {
-                    Ok(Ok(_)) => {}
-                    _ => {
-                        error!("等待重新連線超時");
-                        return Ok(ApiResult::Error);
-                    }
-                }
-            }
+        Ok(self.enqueue(command).await?.result)
+    }
+
+    /// Runs a command and returns every line of the server's reply.
+    pub async fn query(&self, command: Command) -> Result<QueryResponse, ResponseCode> {
+        let command_text = command.to_string();
+        let output = self.enqueue(command).await?;
+
+        match output.result {
+            ApiResult::Timeout | ApiResult::Error => Err(ResponseCode::ServerError),
+            _ => Ok(QueryResponse {
+                command: command_text,
+                success: matches!(output.result, ApiResult::Success(_)),
+                lines: output.lines,
+            }),
+        }
+    }
+
+    async fn enqueue(&self, command: Command) -> Result<CommandOutput, ResponseCode> {
+        // A line break would let the rest of the text run as a second console command
+        if command.to_string().contains(['\r', '\n']) {
+            warn!("拒絕包含換行的命令: {:?}", command);
+            return Err(ResponseCode::InvalidInput);
         }
 
         let (tx, rx) = oneshot::channel();
+        let queued = QueuedCommand {
+            response_timeout: command.response_timeout(),
+            command,
+            reply: tx,
+            enqueued_at: Instant::now(),
+        };
+
+        let depth = self.metrics.depth.fetch_add(1, Ordering::SeqCst) + 1;
+        if depth > 1 {
+            info!("命令已加入佇列，目前佇列長度: {}", depth);
+        }
 
-        {
-            let mut shared_lock = self.shared.lock().await;
+        if self.queue.send(queued).await.is_err() {
+            self.metrics.depth.fetch_sub(1, Ordering::SeqCst);
+            error!("命令佇列已關閉");
+            return Ok(CommandOutput::error());
+        }
 
-            // Re-check connection after acquiring lock
-            if !matches!(shared_lock.status, ConnectionStatus::Connected { .. }) {
-                error!("連線在等待鎖期間斷開");
-                return Ok(ApiResult::Error);
+        match rx.await {
+            Ok(output) => Ok(output),
+            Err(_) => {
+                error!("接收響應失敗，發送通道被關閉");
+                Ok(CommandOutput::error())
             }
+        }
+    }
 
-            if shared_lock.current_sender.is_some() {
-                error!("已有待處理的命令");
-                return Ok(ApiResult::Error);
+    pub fn metrics(&self) -> QueueMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+}
+
+async fn dispatch_loop(
+    mut queue: mpsc::Receiver<QueuedCommand>,
+    shared: Arc<TokioMutex<SharedState>>,
+    connected: watch::Receiver<bool>,
+    metrics: Arc<QueueMetrics>,
+) {
+    while let Some(queued) = queue.recv().await {
+        metrics.depth.fetch_sub(1, Ordering::SeqCst);
+
+        let output = if queued.enqueued_at.elapsed() >= COMMAND_QUEUE_TIMEOUT {
+            warn!("命令在佇列中等待過久，已放棄: {}", queued.command);
+            CommandOutput {
+                result: ApiResult::Timeout,
+                lines: Vec::new(),
             }
-            shared_lock.current_sender = Some(tx);
+        } else {
+            execute_command(
+                &shared,
+                &connected,
+                &queued.command,
+                queued.response_timeout,
+            )
+            .await
+        };
+
+        let latency = queued.enqueued_at.elapsed();
+        metrics.record(latency, matches!(output.result, ApiResult::Timeout));
+        info!(
+            "命令完成 {:?}，耗時 {:?}，佇列長度 {}",
+            output.result,
+            latency,
+            metrics.depth.load(Ordering::SeqCst)
+        );
+
+        if queued.reply.send(output).is_err() {
+            warn!("命令呼叫者已取消等待");
+        }
+    }
+}
 
-            let cmd_str = command.to_string();
-            let write_result = match &mut shared_lock.status {
-                ConnectionStatus::Connected { writer } => {
-                    write_command_raw(writer, cmd_str.as_str()).await
+async fn execute_command(
+    shared: &TokioMutex<SharedState>,
+    connected: &watch::Receiver<bool>,
+    command: &Command,
+    response_timeout: Duration,
+) -> CommandOutput {
+    // Check connection status, wait for reconnection if needed
+    {
+        let shared_lock = shared.lock().await;
+        if !matches!(shared_lock.status, ConnectionStatus::Connected { .. }) {
+            drop(shared_lock);
+            let mut rx = connected.clone();
+            let wait_result = timeout(RECONNECT_COMMAND_WAIT, rx.wait_for(|&c| c)).await;
+            match wait_result {
+                Ok(Ok(_)) => {}
+                _ => {
+                    error!("等待重新連線超時");
+                    return CommandOutput::error();
                 }
-                _ => unreachable!(),
-            };
+            }
+        }
+    }
 
-            if let Err(e) = write_result {
-                shared_lock.current_sender = None;
-                shared_lock.status = ConnectionStatus::Disconnected;
-                error!("寫入命令失敗，連線可能已斷開: {}", e);
-                return Ok(ApiResult::Error);
+    let (line_tx, mut line_rx) = mpsc::unbounded_channel();
+
+    {
+        let mut shared_lock = shared.lock().await;
+
+        // Re-check connection after acquiring lock
+        if !matches!(shared_lock.status, ConnectionStatus::Connected { .. }) {
+            error!("連線在等待鎖期間斷開");
+            return CommandOutput::error();
+        }
+
+        shared_lock.current_command = Some(line_tx);
+
+        let cmd_str = command.to_string();
+        let write_result = match &mut shared_lock.status {
+            ConnectionStatus::Connected { writer } => {
+                write_command_raw(writer, cmd_str.as_str()).await
             }
-            info!(">> {}", command);
+            _ => unreachable!(),
+        };
+
+        if let Err(e) = write_result {
+            shared_lock.current_command = None;
+            shared_lock.status = ConnectionStatus::Disconnected;
+            error!("寫入命令失敗，連線可能已斷開: {}", e);
+            return CommandOutput::error();
         }
+        info!(">> {}", command);
+    }
 
-        match timeout(Duration::from_secs(2), rx).await {
-            Ok(result) => match result {
-                Ok(api_result) => Ok(api_result),
-                Err(_) => {
-                    error!("接收響應失敗，發送通道被關閉");
-                    Ok(ApiResult::Error)
+    let output = collect_response(command, &mut line_rx, response_timeout).await;
+
+    shared.lock().await.current_command = None;
+
+    output
+}
+
+async fn collect_response(
+    command: &Command,
+    line_rx: &mut mpsc::UnboundedReceiver<String>,
+    response_timeout: Duration,
+) -> CommandOutput {
+    let matcher = command.response_matcher();
+    let collects = command.collects_response();
+    let deadline = Instant::now() + response_timeout;
+    let mut lines = Vec::new();
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let wait = if collects && !lines.is_empty() {
+            remaining.min(QUERY_IDLE_TIMEOUT)
+        } else {
+            remaining
+        };
+
+        match timeout(wait, line_rx.recv()).await {
+            Ok(Some(line)) => {
+                // Events from other users arrive on the same connection
+                let Some(line) = ResponseLine::parse(&line) else {
+                    continue;
+                };
+                // Broadcasts are only ever the echo of our own announcement,
+                // never classified by their text
+                if line.kind == LineKind::Broadcast {
+                    if command.is_echo(&line) {
+                        let result = ApiResult::Success(line.text.clone());
+                        lines.push(line);
+                        return CommandOutput { result, lines };
+                    }
+                    continue;
                 }
-            },
+                let result = matcher.classify(&line);
+                lines.push(line);
+                // Collecting commands only stop early on a failure line
+                match result {
+                    Some(ApiResult::Success(_)) if collects => {}
+                    Some(result) => return CommandOutput { result, lines },
+                    None => {}
+                }
+            }
+            // The reader dropped our sender: the connection is gone
+            Ok(None) => {
+                return CommandOutput {
+                    result: ApiResult::Error,
+                    lines,
+                };
+            }
             Err(_) => {
-                let mut shared_lock = self.shared.lock().await;
-                shared_lock.current_sender = None;
-                Ok(ApiResult::Timeout)
+                // Lines that matched nothing are a reply all the same, only
+                // silence is a timeout
+                let result = if lines.is_empty() {
+                    ApiResult::Timeout
+                } else {
+                    let text: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+                    if collects {
+                        ApiResult::Success(text.join("\n"))
+                    } else {
+                        ApiResult::Unknown(text.join("\n"))
+                    }
+                };
+                return CommandOutput { result, lines };
             }
         }
     }
@@ -232,43 +686,38 @@ async fn reader_loop(
     reconnect_notify: Arc<Notify>,
     connected_tx: watch::Sender<bool>,
 ) {
-    let mut buf = [0; 1024];
+    let mut buf = Vec::new();
     loop {
-        match reader.read(&mut buf).await {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
             Ok(0) | Err(_) => {
                 warn!("與伺服器的連線已斷開");
                 let mut shared_lock = shared.lock().await;
                 shared_lock.status = ConnectionStatus::Disconnected;
-                // Notify any waiting send_command
-                if let Some(sender) = shared_lock.current_sender.take() {
-                    let _ = sender.send(ApiResult::Error);
-                }
+                // Dropping the line sender tells the waiting command the connection is gone
+                shared_lock.current_command = None;
                 drop(shared_lock);
                 let _ = connected_tx.send(false);
                 reconnect_notify.notify_one();
                 return;
             }
-            Ok(n) => {
-                let resp = String::from_utf8_lossy(&buf[..n]).to_string();
-
-                let sender = {
-                    let mut shared_lock = shared.lock().await;
-                    shared_lock.current_sender.take()
-                };
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf);
+                let line = line.trim_end_matches(['\r', '\n']);
+                if line.trim().is_empty() {
+                    continue;
+                }
 
-                if let Some(sender) = sender {
-                    let api_result = if resp.is_empty() {
-                        error!("Command Finished with empty response");
-                        ApiResult::Error
-                    } else {
-                        ApiResult::Success
-                    };
+                info!("<< {}", line);
 
-                    if sender.send(api_result).is_err() {
-                        error!("Failed to send response through channel");
+                let shared_lock = shared.lock().await;
+                match &shared_lock.current_command {
+                    Some(line_tx) => {
+                        if line_tx.send(line.to_string()).is_err() {
+                            warn!("Command finished before the response line arrived");
+                        }
                     }
-                } else {
-                    info!("Received response but no active sender found");
+                    None => info!("Received response but no active command found"),
                 }
             }
         }
@@ -330,3 +779,65 @@ async fn reconnect_task(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(command: &Command, line: &str) -> Option<ApiResult> {
+        ResponseLine::parse(line).and_then(|line| command.response_matcher().classify(&line))
+    }
+
+    #[test]
+    fn parses_reply_codes_and_skips_other_events() {
+        let line = ResponseLine::parse(r#"1018 INFO "That user's account is now locked.""#).unwrap();
+        assert_eq!(line.kind, LineKind::Info);
+        assert_eq!(line.text, "That user's account is now locked.");
+
+        let line = ResponseLine::parse(r#"1019 ERROR "Invalid user.""#).unwrap();
+        assert_eq!(line.kind, LineKind::Error);
+        assert_eq!(line.text, "Invalid user.");
+
+        assert!(ResponseLine::parse(r#"1004 WHISPER Saber 0010 "usage: not allowed""#).is_none());
+        assert!(ResponseLine::parse(r#"1005 TALK Saber 0010 "is now locked""#).is_none());
+        assert!(ResponseLine::parse("1002 JOIN Saber 0010 [W3XP]").is_none());
+        assert_eq!(ResponseLine::parse("Password:").unwrap().kind, LineKind::Text);
+    }
+
+    #[test]
+    fn classifies_only_server_replies() {
+        let ban = Command::Ban("saber".to_string(), 60, "spam".to_string());
+        assert!(matches!(
+            classify(&ban, r#"1018 INFO "That user's account is now locked.""#),
+            Some(ApiResult::Success(_))
+        ));
+        assert!(matches!(
+            classify(&ban, r#"1019 ERROR "Something went wrong.""#),
+            Some(ApiResult::Failed(_))
+        ));
+        assert!(matches!(
+            classify(&ban, r#"1018 INFO "usage: /lock <username>""#),
+            Some(ApiResult::Failed(_))
+        ));
+        assert!(classify(&ban, r#"1004 WHISPER Archer 0010 "that's not allowed""#).is_none());
+        assert!(classify(&ban, r#"1004 WHISPER Archer 0010 "Saber is now locked""#).is_none());
+        assert!(classify(&ban, r#"1006 BROADCAST "Saber is now locked""#).is_none());
+    }
+
+    #[test]
+    fn matches_announce_echo_by_event_code() {
+        let announce = Command::Announce("Spamming is not allowed, usage: /report".to_string());
+        let echo = ResponseLine::parse(
+            r#"1006 BROADCAST "Announcement from admin: Spamming is not allowed, usage: /report""#,
+        )
+        .unwrap();
+        assert!(announce.is_echo(&echo));
+        assert!(announce.response_matcher().classify(&echo).is_none());
+
+        let other = ResponseLine::parse(r#"1006 BROADCAST "Server restarts in 5 minutes""#).unwrap();
+        assert!(!announce.is_echo(&other));
+        let whisper =
+            ResponseLine::parse(r#"1004 WHISPER Saber 0010 "Spamming is not allowed, usage: /report""#);
+        assert!(whisper.is_none());
+    }
+}
@@ -1 +1,3 @@
-pub mod file;
\ No newline at end of file
+pub mod file;
+pub mod server_dat;
+pub mod w3mmd;
\ No newline at end of file
@@ -0,0 +1,342 @@
+//! Parser for the PvPGN `server.dat` status file.
+//!
+//! Each entry is a `key=value` line whose value is comma separated:
+//!
+//! ```text
+//! game1=W3XP,2,Room Name[,game type][,created unix time]
+//! user1=W3XP,UserName,...,2
+//! ```
+//!
+//! Room names may contain commas, so the optional trailing fields are only
+//! split off when they look like a creation time or a PvPGN game type.
+//!
+//! A user line with five fields is in the game whose id is the last field,
+//! shorter user lines and lines with an empty last field are online users
+//! outside any game. Older files without the `gameN=` / `userN=` keys are
+//! recognised by their field count.
+
+use crate::model::room::{RoomInfo, ServerStatus};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+
+const USER_IN_GAME_FIELDS: usize = 5;
+
+/// Trailing numbers below this (2001-09-09) are part of the room name, not its creation time.
+const MIN_CREATED_AT: i64 = 1_000_000_000;
+
+/// Game types PvPGN writes for a game, compared case-insensitively.
+const GAME_TYPES: &[&str] = &[
+    "normal",
+    "melee",
+    "ffa",
+    "oneonone",
+    "ctf",
+    "greed",
+    "slaughter",
+    "sdeath",
+    "ladder",
+    "ironman",
+    "mapset",
+    "teammelee",
+    "teamffa",
+    "teamctf",
+    "pgl",
+    "topvbot",
+    "private",
+    "ums",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    InvalidRoomId { line: usize, value: String },
+    MissingField { line: usize, field: &'static str },
+    UnknownRoom { line: usize, room_id: u16 },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidRoomId { line, value } => {
+                write!(f, "line {}: invalid room id {:?}", line, value)
+            }
+            ParseError::MissingField { line, field } => {
+                write!(f, "line {}: missing {}", line, field)
+            }
+            ParseError::UnknownRoom { line, room_id } => {
+                write!(f, "line {}: user in unknown room {}", line, room_id)
+            }
+        }
+    }
+}
+
+enum Entry<'a> {
+    Game(Vec<&'a str>),
+    User(Vec<&'a str>),
+    Other,
+}
+
+fn classify_line(line: &str) -> Entry<'_> {
+    let (key, value) = match line.split_once('=') {
+        Some((key, value)) if !key.contains(',') => (key.trim().to_lowercase(), value),
+        _ => (String::new(), line),
+    };
+    // Left untrimmed so room names keep the spaces around their commas
+    let fields: Vec<&str> = value.split(',').collect();
+
+    if key.starts_with("game") {
+        Entry::Game(fields)
+    } else if key.starts_with("user") {
+        Entry::User(fields)
+    } else if !key.is_empty() {
+        Entry::Other
+    } else {
+        match fields.len() {
+            3 => Entry::Game(fields),
+            USER_IN_GAME_FIELDS => Entry::User(fields),
+            _ => Entry::Other,
+        }
+    }
+}
+
+fn parse_room_id(line: usize, value: Option<&&str>) -> Result<u16, ParseError> {
+    let value = value.ok_or(ParseError::MissingField {
+        line,
+        field: "room id",
+    })?;
+    value.trim().parse().map_err(|_| ParseError::InvalidRoomId {
+        line,
+        value: value.trim().to_string(),
+    })
+}
+
+/// Splits the fields after the room id into room name, game type and creation time.
+fn parse_game_tail(mut tail: &[&str]) -> (String, Option<String>, Option<DateTime<Utc>>) {
+    let mut created_at = None;
+    if let [name @ .., last] = tail
+        && !name.is_empty()
+        && let Ok(time) = last.trim().parse::<i64>()
+        && time >= MIN_CREATED_AT
+    {
+        created_at = DateTime::from_timestamp(time, 0);
+        tail = name;
+    }
+
+    let mut game_type = None;
+    if let [name @ .., last] = tail
+        && !name.is_empty()
+    {
+        let last = last.trim();
+        if GAME_TYPES.iter().any(|t| t.eq_ignore_ascii_case(last)) {
+            game_type = Some(last.to_string());
+            tail = name;
+        } else if last.is_empty() {
+            tail = name;
+        }
+    }
+
+    (tail.join(",").trim().to_string(), game_type, created_at)
+}
+
+/// Parses the whole file, skipping malformed lines instead of failing.
+/// The returned errors describe every skipped line.
+pub fn parse(content: &str) -> (ServerStatus, Vec<ParseError>) {
+    let mut rooms: BTreeMap<u16, RoomInfo> = BTreeMap::new();
+    let mut users: Vec<(usize, String, u16)> = Vec::new();
+    let mut lobby_users = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('[') {
+            continue;
+        }
+
+        match classify_line(line) {
+            Entry::Game(fields) => {
+                let room_id = match parse_room_id(line_no, fields.get(1)) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
+                let (room_name, game_type, created_at) = match fields.get(2..) {
+                    Some(tail) if !tail.is_empty() => parse_game_tail(tail),
+                    _ => (String::new(), None, None),
+                };
+                if room_name.is_empty() {
+                    errors.push(ParseError::MissingField {
+                        line: line_no,
+                        field: "room name",
+                    });
+                    continue;
+                }
+
+                rooms.insert(
+                    room_id,
+                    RoomInfo {
+                        room_id,
+                        room_name,
+                        player_count: 0,
+                        players: Vec::new(),
+                        game_type,
+                        created_at,
+                    },
+                );
+            }
+            Entry::User(fields) => {
+                let username = match fields.get(1).map(|u| u.trim()).filter(|u| !u.is_empty()) {
+                    Some(username) => username.to_string(),
+                    None => {
+                        errors.push(ParseError::MissingField {
+                            line: line_no,
+                            field: "username",
+                        });
+                        continue;
+                    }
+                };
+                let in_game = fields.len() >= USER_IN_GAME_FIELDS
+                    && fields.last().is_some_and(|id| !id.trim().is_empty());
+                if !in_game {
+                    lobby_users.push(username);
+                    continue;
+                }
+                match parse_room_id(line_no, fields.last()) {
+                    Ok(room_id) => users.push((line_no, username, room_id)),
+                    Err(e) => errors.push(e),
+                }
+            }
+            Entry::Other => {}
+        }
+    }
+
+    // Users may be listed before their game, so attach them once all rooms are known
+    for (line, username, room_id) in users {
+        match rooms.get_mut(&room_id) {
+            Some(room) => {
+                room.player_count = room.player_count.saturating_add(1);
+                room.players.push(username);
+            }
+            None => errors.push(ParseError::UnknownRoom { line, room_id }),
+        }
+    }
+
+    let status = ServerStatus {
+        rooms: rooms.into_values().collect(),
+        lobby_users,
+    };
+    (status, errors)
+}
+
+pub fn read(path: &str) -> io::Result<(ServerStatus, Vec<ParseError>)> {
+    let content = fs::read(path)?;
+    Ok(parse(&String::from_utf8_lossy(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rooms_players_and_lobby_users() {
+        let content = "\
+[GAMES]
+game1=W3XP,2,Fate Another,melee,1760781600
+[USERS]
+user1=W3XP,Saber,1,0,2
+user2=W3XP,Archer
+user3=W3XP,Lancer,1,0,
+";
+        let (status, errors) = parse(content);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(status.rooms.len(), 1);
+        let room = &status.rooms[0];
+        assert_eq!(room.room_id, 2);
+        assert_eq!(room.room_name, "Fate Another");
+        assert_eq!(room.game_type.as_deref(), Some("melee"));
+        assert_eq!(room.created_at.map(|t| t.timestamp()), Some(1760781600));
+        assert_eq!(room.players, vec!["Saber"]);
+        assert_eq!(status.lobby_users, vec!["Archer", "Lancer"]);
+    }
+
+    #[test]
+    fn keeps_commas_in_room_names() {
+        let content = "\
+game1=W3XP,1,Hello, World
+game2=W3XP,2,Saber, Archer, Lancer,ffa,1760781600
+game3=W3XP,3,Round, 5
+";
+        let (status, errors) = parse(content);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        let names: Vec<_> = status.rooms.iter().map(|r| r.room_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Hello, World", "Saber, Archer, Lancer", "Round, 5"]
+        );
+        assert_eq!(status.rooms[0].game_type, None);
+        assert_eq!(status.rooms[1].game_type.as_deref(), Some("ffa"));
+        assert!(status.rooms[1].created_at.is_some());
+        assert_eq!(status.rooms[2].created_at, None);
+    }
+
+    #[test]
+    fn parses_files_without_keys() {
+        let content = "\
+W3XP,4,Old Room
+W3XP,Rider,1,0,4
+";
+        let (status, errors) = parse(content);
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(status.rooms[0].room_name, "Old Room");
+        assert_eq!(status.rooms[0].players, vec!["Rider"]);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let content = "\
+game1=W3XP,abc,Broken Room
+game2=W3XP,2
+game3=W3XP,3,Good Room
+user1=W3XP,,1,0,3
+user2=W3XP,Caster,1,0,x
+user3=W3XP,Berserker,1,0,9
+user4=W3XP,Assassin,1,0,3
+";
+        let (status, errors) = parse(content);
+
+        assert_eq!(
+            errors,
+            vec![
+                ParseError::InvalidRoomId {
+                    line: 1,
+                    value: "abc".to_string()
+                },
+                ParseError::MissingField {
+                    line: 2,
+                    field: "room name"
+                },
+                ParseError::MissingField {
+                    line: 4,
+                    field: "username"
+                },
+                ParseError::InvalidRoomId {
+                    line: 5,
+                    value: "x".to_string()
+                },
+                ParseError::UnknownRoom {
+                    line: 6,
+                    room_id: 9
+                },
+            ]
+        );
+        assert_eq!(status.rooms.len(), 1);
+        assert_eq!(status.rooms[0].players, vec!["Assassin"]);
+    }
+}
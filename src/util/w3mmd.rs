@@ -0,0 +1,297 @@
+//! w3mmd var schemas.
+//!
+//! Maps report their state through `w3mmdvars` rows whose names and value
+//! formats differ from map to map. A schema, `[[w3mmd_schemas]]` in settings,
+//! names the vars a map uses and how its string values are split:
+//!
+//! ```text
+//! servant   = "1:Saber"           team index and servant name
+//! team_info = "1:Red Team:25"     team index, team name and score
+//! ```
+//!
+//! Games are matched against the configured schemas in order by `games.map`
+//! and the w3mmd category; games matching none use [`W3mmdSchema::default`],
+//! which is the Fate Another layout above.
+
+use std::collections::{BTreeSet, HashMap};
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use tracing::warn;
+
+use crate::settings::CONFIG;
+
+static DEFAULT_SCHEMA: Lazy<W3mmdSchema> = Lazy::new(W3mmdSchema::default);
+
+/// Games per vars query in [`fetch_player_vars`], keeps the bind count low.
+const VARS_GAMES_PER_QUERY: usize = 1000;
+
+/// How a player's team index is derived.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "from", rename_all = "snake_case")]
+pub enum TeamSource {
+    /// A field of the servant var.
+    ServantField { field: usize },
+    /// An integer var of its own.
+    Var { name: String },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct W3mmdSchema {
+    /// Case insensitive part of `games.map` selecting this schema.
+    pub map_pattern: Option<String>,
+    /// w3mmd category selecting this schema, either selector is enough.
+    pub category: Option<String>,
+    /// Separator between the fields of string vars.
+    pub separator: String,
+    pub servant_var: String,
+    /// Field of the servant var holding the servant name.
+    pub servant_name_field: usize,
+    pub team: TeamSource,
+    /// Var with one `index:name:score` row per team. This and the vars below
+    /// are left empty if the map does not report them.
+    pub team_info_var: String,
+    pub team_info_index_field: usize,
+    pub team_info_name_field: usize,
+    pub team_info_score_field: usize,
+    pub kills_var: String,
+    pub deaths_var: String,
+    pub assists_var: String,
+    pub level_var: String,
+}
+
+impl Default for W3mmdSchema {
+    fn default() -> Self {
+        W3mmdSchema {
+            map_pattern: None,
+            category: None,
+            separator: ":".to_string(),
+            servant_var: "servant".to_string(),
+            servant_name_field: 1,
+            team: TeamSource::ServantField { field: 0 },
+            team_info_var: "team_info".to_string(),
+            team_info_index_field: 0,
+            team_info_name_field: 1,
+            team_info_score_field: 2,
+            kills_var: "kills".to_string(),
+            deaths_var: "deaths".to_string(),
+            assists_var: "assists".to_string(),
+            level_var: "level".to_string(),
+        }
+    }
+}
+
+/// A `w3mmdvars` row.
+#[derive(Debug, sqlx::FromRow)]
+pub struct W3mmdVar {
+    pub gameid: i32,
+    pub pid: i32,
+    pub varname: String,
+    pub value_int: Option<i32>,
+    pub value_string: Option<String>,
+}
+
+/// Player values read through a schema.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerVars {
+    /// Raw servant var, `None` if the map did not report one for the player.
+    pub servant_raw: Option<String>,
+    pub servant: Option<String>,
+    pub team: Option<i32>,
+    pub kills: Option<i32>,
+    pub deaths: Option<i32>,
+    pub assists: Option<i32>,
+    pub level: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamInfo {
+    pub index: i32,
+    pub name: String,
+    pub score: i32,
+}
+
+impl W3mmdSchema {
+    pub fn matches(&self, map: &str, category: &str) -> bool {
+        let map_matches = self
+            .map_pattern
+            .as_ref()
+            .is_some_and(|p| !p.is_empty() && map.to_lowercase().contains(&p.to_lowercase()));
+        let category_matches = self
+            .category
+            .as_ref()
+            .is_some_and(|c| !c.is_empty() && c == category);
+        map_matches || category_matches
+    }
+
+    /// Every var name this schema reads.
+    pub fn var_names(&self) -> Vec<&str> {
+        let mut names = vec![self.servant_var.as_str()];
+        if let TeamSource::Var { name } = &self.team {
+            names.push(name.as_str());
+        }
+        names.extend([
+            self.team_info_var.as_str(),
+            self.kills_var.as_str(),
+            self.deaths_var.as_str(),
+            self.assists_var.as_str(),
+            self.level_var.as_str(),
+        ]);
+        names.retain(|name| !name.is_empty());
+        names
+    }
+
+    fn fields<'a>(&self, raw: &'a str) -> Vec<&'a str> {
+        raw.trim_matches('"').split(self.separator.as_str()).collect()
+    }
+
+    /// Servant name and, if the team comes from the servant var, team index.
+    pub fn parse_servant(&self, raw: &str) -> Option<(Option<i32>, String)> {
+        let fields = self.fields(raw);
+        let Some(name) = fields.get(self.servant_name_field) else {
+            warn!("servant info format invalid: {}", raw);
+            return None;
+        };
+        let team = match self.team {
+            TeamSource::ServantField { field } => {
+                match fields.get(field).and_then(|f| f.parse::<i32>().ok()) {
+                    Some(team) => Some(team),
+                    None => {
+                        warn!("servant info format invalid: {}", raw);
+                        return None;
+                    }
+                }
+            }
+            TeamSource::Var { .. } => None,
+        };
+        Some((team, name.to_string()))
+    }
+
+    pub fn parse_team_info(&self, raw: &str) -> Option<TeamInfo> {
+        let fields = self.fields(raw);
+        let index = fields.get(self.team_info_index_field).and_then(|f| f.parse().ok());
+        let name = fields.get(self.team_info_name_field);
+        let score = fields.get(self.team_info_score_field).and_then(|f| f.parse().ok());
+        match (index, name, score) {
+            (Some(index), Some(name), Some(score)) => Some(TeamInfo {
+                index,
+                name: name.to_string(),
+                score,
+            }),
+            _ => {
+                warn!("team info format invalid: {}", raw);
+                None
+            }
+        }
+    }
+
+    pub fn is_team_info(&self, var: &W3mmdVar) -> bool {
+        !self.team_info_var.is_empty() && var.varname == self.team_info_var
+    }
+
+    /// Reads a player var into `vars`, vars the schema does not know are ignored.
+    pub fn apply(&self, var: &W3mmdVar, vars: &mut PlayerVars) {
+        let name = var.varname.as_str();
+        let team_var = match &self.team {
+            TeamSource::Var { name } => name.as_str(),
+            TeamSource::ServantField { .. } => "",
+        };
+        if name.is_empty() {
+            return;
+        }
+
+        if name == self.servant_var {
+            vars.servant_raw = var.value_string.clone();
+            let Some(raw) = var.value_string.as_deref() else {
+                return;
+            };
+            if let Some((team, servant)) = self.parse_servant(raw) {
+                vars.servant = Some(servant);
+                if team.is_some() {
+                    vars.team = team;
+                }
+            }
+        } else if name == team_var {
+            vars.team = var.value_int;
+        } else if name == self.kills_var {
+            vars.kills = var.value_int;
+        } else if name == self.deaths_var {
+            vars.deaths = var.value_int;
+        } else if name == self.assists_var {
+            vars.assists = var.value_int;
+        } else if name == self.level_var {
+            vars.level = var.value_int;
+        }
+    }
+}
+
+/// Schema of a game, the first configured match or the default.
+pub fn schema_for(map: &str, category: &str) -> &'static W3mmdSchema {
+    CONFIG
+        .w3mmd_schemas
+        .iter()
+        .find(|schema| schema.matches(map, category))
+        .unwrap_or(&DEFAULT_SCHEMA)
+}
+
+/// Configured schemas followed by the default.
+pub fn schemas() -> impl Iterator<Item = &'static W3mmdSchema> {
+    CONFIG.w3mmd_schemas.iter().chain(std::iter::once(&*DEFAULT_SCHEMA))
+}
+
+/// Vars read by any schema for the given games, all games if `game_ids` is `None`.
+pub async fn fetch_vars(
+    pool: &MySqlPool,
+    game_ids: Option<&[i32]>,
+) -> Result<Vec<W3mmdVar>, sqlx::Error> {
+    if game_ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+    let var_names: BTreeSet<&str> = schemas().flat_map(|schema| schema.var_names()).collect();
+
+    let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+        "SELECT gameid, pid, varname, value_int, value_string FROM w3mmdvars WHERE varname IN (",
+    );
+    let mut separated = builder.separated(", ");
+    for name in var_names {
+        separated.push_bind(name);
+    }
+    separated.push_unseparated(")");
+    if let Some(game_ids) = game_ids {
+        builder.push(" AND gameid IN (");
+        let mut separated = builder.separated(", ");
+        for id in game_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(")");
+    }
+    builder.build_query_as::<W3mmdVar>().fetch_all(pool).await
+}
+
+/// Vars of the given players keyed by `(gameid, pid)`, each read through its schema.
+///
+/// Players the maps reported no vars for are left out.
+pub async fn fetch_player_vars(
+    pool: &MySqlPool,
+    players: &HashMap<(i32, i32), &'static W3mmdSchema>,
+) -> Result<HashMap<(i32, i32), PlayerVars>, sqlx::Error> {
+    let game_ids: Vec<i32> = players
+        .keys()
+        .map(|(gameid, _)| *gameid)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut vars: HashMap<(i32, i32), PlayerVars> = HashMap::new();
+    for chunk in game_ids.chunks(VARS_GAMES_PER_QUERY) {
+        for var in fetch_vars(pool, Some(chunk)).await? {
+            let key = (var.gameid, var.pid);
+            if let Some(schema) = players.get(&key) {
+                schema.apply(&var, vars.entry(key).or_default());
+            }
+        }
+    }
+    Ok(vars)
+}
@@ -1,22 +1,281 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 use chrono::Utc;
-use sqlx::MySqlPool;
-use tokio::sync::broadcast;
-use tokio::time::{interval, Duration};
-use tracing::{error, info, warn};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Notify;
+use tokio::time::{interval, sleep_until, Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 use crate::database::mysql_pool;
+use crate::model::mmr::MmrWorkerStatus;
+use crate::settings::CONFIG;
+use crate::util::w3mmd::{self, PlayerVars};
+use crate::worker::room_watcher::{RoomEvent, RoomWatcher};
+use crate::worker::rating::{
+    rating_system, MmrContext, Outcome, Rating, RatingSystem, TeamRating,
+};
+
+mod recompute;
+mod season;
+mod void;
+
+pub use recompute::{apply_shadow, replay_into_shadow, start_recompute};
+pub use season::{start_season, SeasonRules};
+pub use void::void_game;
+
+const DECAY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Rows per multi-row statement, keeps the bind count well below MySQL's limit.
+const INSERT_CHUNK_SIZE: usize = 500;
+
+/// Players with a rated flag and their game's server and map.
+/// Callers append further `AND` conditions and the ordering.
+const GAME_PLAYERS_QUERY: &str = r#"
+        SELECT p.gameid, p.pid, p.name, p.category, g.server, g.map, p.flag
+        FROM w3mmdplayers p
+        JOIN games g ON g.id = p.gameid
+        WHERE p.flag IN ('winner', 'loser', 'drawer', 'leaver')
+"#;
 
 static IS_PROCESSING: AtomicBool = AtomicBool::new(false);
+static WORKER_STATUS: Lazy<Mutex<MmrWorkerStatus>> = Lazy::new(Default::default);
+static WAKE: Lazy<Notify> = Lazy::new(Notify::new);
+
+// w3mmd player flags, also written to `score_change_logs.result_flag`
+const FLAG_WINNER: &str = "winner";
+const FLAG_LOSER: &str = "loser";
+const FLAG_DRAWER: &str = "drawer";
+const FLAG_LEAVER: &str = "leaver";
+/// Result flag of a loser whose loss was reduced because a teammate left.
+const FLAG_PROTECTED: &str = "protected";
+
+/// How w3mmd flags other than winner/loser are rated, `[mmr_rules]` in settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MmrRules {
+    /// Rate players flagged `leaver` as a loss instead of skipping them.
+    pub rate_leavers: bool,
+    /// Extra points taken from a leaver on top of the loss.
+    pub leaver_penalty: f64,
+    /// Share of a loss kept by the teammates of a leaver, 0 = no loss at all.
+    pub leaver_teammate_loss_factor: f64,
+    /// Rate players flagged `drawer` with a draw result.
+    pub rate_draws: bool,
+}
+
+impl Default for MmrRules {
+    fn default() -> Self {
+        MmrRules {
+            rate_leavers: true,
+            leaver_penalty: 10.0,
+            leaver_teammate_loss_factor: 0.5,
+            rate_draws: true,
+        }
+    }
+}
+
+/// Worker settings, `[mmr_worker]` in settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MmrWorkerConfig {
+    /// Games fetched, rated and written per transaction.
+    pub batch_size: usize,
+    /// Delay after a run that found games, also the first backoff step.
+    pub min_interval_secs: u64,
+    /// Longest delay the idle backoff grows to.
+    pub max_interval_secs: u64,
+}
+
+impl Default for MmrWorkerConfig {
+    fn default() -> Self {
+        MmrWorkerConfig {
+            batch_size: 200,
+            min_interval_secs: 5,
+            max_interval_secs: 300,
+        }
+    }
+}
+
+/// Optional scaling of MMR changes by in-game performance, `[mmr_performance]` in settings.
+///
+/// A player's contribution is `kills * kill_weight + assists * assist_weight +
+/// level * level_weight - deaths * death_weight` (never below 0). Its ratio to
+/// the team average, capped to `1 ± max_modifier`, multiplies gains, while
+/// losses are multiplied by the mirrored value so strong players lose less.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PerformanceRules {
+    pub enabled: bool,
+    pub kill_weight: f64,
+    pub death_weight: f64,
+    pub assist_weight: f64,
+    pub level_weight: f64,
+    pub max_modifier: f64,
+}
+
+impl Default for PerformanceRules {
+    fn default() -> Self {
+        PerformanceRules {
+            enabled: false,
+            kill_weight: 1.0,
+            death_weight: 1.0,
+            assist_weight: 0.5,
+            level_weight: 0.0,
+            max_modifier: 0.25,
+        }
+    }
+}
+
+impl PerformanceRules {
+    fn contribution(&self, p: &GamePlayer) -> f64 {
+        let value = |v: Option<i32>| v.unwrap_or(0) as f64;
+        let score = value(p.vars.kills) * self.kill_weight
+            + value(p.vars.assists) * self.assist_weight
+            + value(p.vars.level) * self.level_weight
+            - value(p.vars.deaths) * self.death_weight;
+        score.max(0.0)
+    }
+
+    /// Modifier for a contribution relative to the team average, 1.0 = neutral.
+    fn modifier(&self, contribution: f64, team_avg: f64) -> f64 {
+        if !self.enabled || team_avg <= 0.0 {
+            return 1.0;
+        }
+        (contribution / team_avg).clamp(1.0 - self.max_modifier, 1.0 + self.max_modifier)
+    }
+}
+
+impl MmrRules {
+    fn is_rated(&self, flag: &str) -> bool {
+        match flag {
+            FLAG_WINNER | FLAG_LOSER => true,
+            FLAG_LEAVER => self.rate_leavers,
+            FLAG_DRAWER => self.rate_draws,
+            _ => false,
+        }
+    }
+}
+
+/// Errors of the admin operations (recompute, void) on the MMR tables.
+#[derive(Debug)]
+pub enum MmrAdminError {
+    /// The worker or another admin operation holds the processing lock.
+    Busy,
+    /// `apply` was called before a replay built the shadow tables.
+    NoShadowTables,
+    GameNotFound,
+    AlreadyVoided,
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for MmrAdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MmrAdminError::Busy => write!(f, "MMR processing is already running"),
+            MmrAdminError::NoShadowTables => {
+                write!(f, "no replayed ratings to apply, run a recompute first")
+            }
+            MmrAdminError::GameNotFound => write!(f, "game not found"),
+            MmrAdminError::AlreadyVoided => write!(f, "game is already voided"),
+            MmrAdminError::Database(err) => write!(f, "database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MmrAdminError {}
+
+impl From<sqlx::Error> for MmrAdminError {
+    fn from(err: sqlx::Error) -> Self {
+        MmrAdminError::Database(err)
+    }
+}
 
-pub fn start_mmr_worker(mut shutdown_rx: broadcast::Receiver<()>) {
+/// Holds the worker's processing flag, released on drop.
+struct ProcessingGuard;
+
+impl ProcessingGuard {
+    fn acquire() -> Result<Self, MmrAdminError> {
+        IS_PROCESSING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map(|_| ProcessingGuard)
+            .map_err(|_| MmrAdminError::Busy)
+    }
+}
+
+impl Drop for ProcessingGuard {
+    fn drop(&mut self) {
+        IS_PROCESSING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Current worker state with a fresh backlog count.
+pub async fn worker_status(pool: &MySqlPool) -> Result<MmrWorkerStatus, sqlx::Error> {
+    let mut status = WORKER_STATUS.lock().map(|s| s.clone()).unwrap_or_default();
+    status.processing = IS_PROCESSING.load(Ordering::SeqCst);
+    status.backlog = count_unprocessed_games(pool).await?;
+    status.recompute = recompute::recompute_status();
+    Ok(status)
+}
+
+/// Wakes the worker for an immediate run instead of waiting for its schedule.
+pub fn trigger_processing() {
+    WAKE.notify_one();
+}
+
+fn update_status(update: impl FnOnce(&mut MmrWorkerStatus)) {
+    if let Ok(mut status) = WORKER_STATUS.lock() {
+        update(&mut status);
+    }
+}
+
+fn record_error(message: String) {
+    update_status(|status| {
+        status.last_error = Some(message);
+        status.last_error_at = Some(Utc::now().naive_utc());
+    });
+}
+
+/// Runs immediately after work was found and backs off exponentially while idle.
+/// A closed room usually means a finished game is about to be written, so it
+/// pulls the next run in to the minimum interval.
+pub fn start_mmr_worker(room_watcher: RoomWatcher, mut shutdown_rx: broadcast::Receiver<()>) {
     tokio::spawn(async move {
-        let mut ticker = interval(Duration::from_secs(5));
+        let min_delay = Duration::from_secs(CONFIG.mmr_worker.min_interval_secs);
+        let max_delay = Duration::from_secs(CONFIG.mmr_worker.max_interval_secs);
+        let mut delay = min_delay;
+        let mut next_run = Instant::now();
+        let mut room_events = room_watcher.subscribe_events();
+        let mut room_events_open = true;
+        let mut decay_ticker = interval(DECAY_INTERVAL);
         loop {
+            update_status(|status| {
+                let wait = next_run.saturating_duration_since(Instant::now());
+                status.next_run_at = Some(
+                    Utc::now().naive_utc()
+                        + chrono::Duration::from_std(wait).unwrap_or(chrono::Duration::zero()),
+                );
+            });
+
+            let mut decay_due = false;
             tokio::select! {
-                _ = ticker.tick() => {},
+                _ = sleep_until(next_run) => {},
+                _ = WAKE.notified() => {},
+                _ = decay_ticker.tick() => decay_due = true,
+                event = room_events.recv(), if room_events_open => {
+                    match event {
+                        Ok(RoomEvent::Removed { .. }) => {
+                            next_run = next_run.min(Instant::now() + min_delay);
+                        }
+                        Err(RecvError::Closed) => room_events_open = false,
+                        _ => {}
+                    }
+                    continue;
+                }
                 _ = shutdown_rx.recv() => {
                     info!("MMR worker received shutdown signal");
                     break;
@@ -28,43 +287,128 @@ pub fn start_mmr_worker(mut shutdown_rx: broadcast::Receiver<()>) {
                 .is_err()
             {
                 warn!("MMR worker: previous round not finished, skipping");
+                next_run = Instant::now() + min_delay;
                 continue;
             }
-            let result = process_all_mmr().await;
-            IS_PROCESSING.store(false, Ordering::SeqCst);
-            if let Err(e) = result {
-                error!("MMR worker error: {}", e);
+            let mut result = process_all_mmr().await;
+            if decay_due && result.is_ok() {
+                result = season::apply_decay(mysql_pool()).await.map(|_| 0);
             }
+            IS_PROCESSING.store(false, Ordering::SeqCst);
+
+            delay = match result {
+                Ok(processed) if processed > 0 => min_delay,
+                Ok(_) => (delay * 2).clamp(min_delay, max_delay),
+                Err(e) => {
+                    error!("MMR worker error: {}", e);
+                    record_error(e.to_string());
+                    (delay * 2).clamp(min_delay, max_delay)
+                }
+            };
+            next_run = Instant::now() + delay;
         }
         info!("MMR worker shutdown complete");
     });
 }
 
-async fn process_all_mmr() -> Result<(), sqlx::Error> {
+/// Processes the whole backlog and returns the number of games handled.
+async fn process_all_mmr() -> Result<usize, sqlx::Error> {
     let pool = mysql_pool();
-    let ids = get_unprocessed_game_ids(pool).await?;
-    if ids.is_empty() {
-        return Ok(());
+    let backlog = count_unprocessed_games(pool).await?;
+    update_status(|status| {
+        status.backlog = backlog;
+        status.last_run_at = Some(Utc::now().naive_utc());
+    });
+    if backlog == 0 {
+        return Ok(0);
     }
-    info!("MMR worker: processing {} unprocessed games", ids.len());
-    for id in ids {
-        if let Err(e) = process_game_mmr(pool, id).await {
-            error!("Game {} MMR processing failed: {}", id, e);
+    let batch_size = CONFIG.mmr_worker.batch_size;
+    info!("MMR worker: processing {} unprocessed games", backlog);
+
+    let started = Instant::now();
+    let mut after_id = 0;
+    let mut processed = 0;
+    loop {
+        let ids = get_unprocessed_game_ids(pool, after_id, batch_size).await?;
+        let Some(&last_id) = ids.last() else {
+            break;
+        };
+        after_id = last_id;
+
+        // Games are rated in order, so a game that keeps failing stops the
+        // round and is retried first in the next one
+        let mut stuck = false;
+        let succeeded = match process_games(pool, &ids).await {
+            Ok(()) => ids.len(),
+            Err(e) => {
+                warn!("MMR batch up to GameID {} failed, retrying one by one: {}", last_id, e);
+                let mut succeeded = 0;
+                for &id in &ids {
+                    if let Err(e) = process_games(pool, &[id]).await {
+                        error!("Game {} MMR processing failed, stopping this round: {}", id, e);
+                        record_error(format!("Game {}: {}", id, e));
+                        stuck = true;
+                        break;
+                    }
+                    succeeded += 1;
+                }
+                succeeded
+            }
+        };
+
+        processed += succeeded;
+        let rate = processed as f64 / started.elapsed().as_secs_f64().max(0.001);
+        update_status(|status| {
+            if let Some(&id) = succeeded.checked_sub(1).and_then(|i| ids.get(i)) {
+                status.last_processed_game_id = Some(id);
+            }
+            status.backlog = (backlog - processed as i64).max(0);
+            status.games_processed += succeeded as u64;
+            status.games_per_second = Some(rate);
+        });
+        if backlog > batch_size as i64 {
+            info!(
+                "MMR worker: {}/{} games processed ({:.1} games/s)",
+                processed, backlog, rate
+            );
+        }
+        if stuck {
+            break;
         }
     }
-    Ok(())
+    Ok(processed)
+}
+
+async fn count_unprocessed_games(pool: &MySqlPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM games g
+        LEFT JOIN game_mmr_processed p ON g.id = p.gameid
+        WHERE p.id IS NULL
+        "#,
+    )
+    .fetch_one(pool)
+    .await
 }
 
-async fn get_unprocessed_game_ids(pool: &MySqlPool) -> Result<Vec<i32>, sqlx::Error> {
+async fn get_unprocessed_game_ids(
+    pool: &MySqlPool,
+    after_id: i32,
+    limit: usize,
+) -> Result<Vec<i32>, sqlx::Error> {
     let ids = sqlx::query_scalar::<_, i32>(
         r#"
         SELECT g.id
         FROM games g
         LEFT JOIN game_mmr_processed p ON g.id = p.gameid
-        WHERE p.id IS NULL
+        WHERE p.id IS NULL AND g.id > ?
         ORDER BY g.id
+        LIMIT ?
         "#,
     )
+    .bind(after_id)
+    .bind(limit as i64)
     .fetch_all(pool)
     .await?;
     Ok(ids)
@@ -75,75 +419,63 @@ async fn get_unprocessed_game_ids(pool: &MySqlPool) -> Result<Vec<i32>, sqlx::Er
 #[derive(Debug, sqlx::FromRow)]
 #[allow(dead_code)]
 struct GamePlayer {
+    gameid: i32,
     pid: i32,
     name: String,
     category: String,
+    server: String,
+    map: String,
     flag: String,
-    servant_raw: Option<String>,
-}
-
-#[derive(Debug, Clone)]
-struct MmrContext {
-    player_current_mmr: f64,
-    player_team_avg_mmr: f64,
-    opponent_team_avg_mmr: f64,
-    is_winner: bool,
+    /// Team and performance vars, read through the game's w3mmd schema.
+    #[sqlx(skip)]
+    vars: PlayerVars,
+    /// Someone on the player's team left, unrated leavers included.
+    #[sqlx(skip)]
+    leaver_on_team: bool,
 }
 
 #[derive(Debug, Clone)]
 struct MmrResult {
     delta: f64,
-    new_mmr: f64,
+    rating: Rating,
+}
+
+impl MmrResult {
+    fn new(old: &Rating, rating: Rating) -> Self {
+        MmrResult {
+            delta: rating.mmr - old.mmr,
+            rating,
+        }
+    }
+
+    /// Replaces the MMR change, keeping the rest of the new rating state.
+    fn with_delta(mut self, old: &Rating, delta: f64) -> Self {
+        self.rating.mmr = (old.mmr + delta).max(0.0);
+        self.delta = self.rating.mmr - old.mmr;
+        self
+    }
 }
 
 // ── Pure calculation ──
 
-fn calculate_mmr(ctx: &MmrContext) -> MmrResult {
-    // Elo-based MMR system
-    //
-    // K-factor: base points at stake per game.
-    // Higher K = faster rating movement, lower K = more stable ratings.
-    const K: f64 = 32.0;
-
-    // Expected win probability using logistic function:
-    //   E = 1 / (1 + 10^((opponent_avg - team_avg) / 400))
-    //
-    // When teams are equal: E = 0.5
-    // When team is 200 pts stronger: E ≈ 0.76
-    // When team is 200 pts weaker:   E ≈ 0.24
-    let rating_diff = ctx.opponent_team_avg_mmr - ctx.player_team_avg_mmr;
-    let expected = 1.0 / (1.0 + 10.0_f64.powf(rating_diff / 400.0));
-
-    let actual = if ctx.is_winner { 1.0 } else { 0.0 };
-
-    // Delta = K * (actual - expected)
-    //
-    // Examples (equal teams, expected=0.5):
-    //   winner: 32 * (1.0 - 0.5) = +16
-    //   loser:  32 * (0.0 - 0.5) = -16
-    //
-    // Weak team (expected=0.24) beats strong team:
-    //   winner: 32 * (1.0 - 0.24) = +24.3  (big reward)
-    //   loser:  32 * (0.0 - 0.76) = -24.3  (big penalty)
-    //
-    // Strong team (expected=0.76) beats weak team:
-    //   winner: 32 * (1.0 - 0.76) = +7.7   (small reward)
-    //   loser:  32 * (0.0 - 0.24) = -7.7   (small penalty)
-    let delta = K * (actual - expected);
-    let new_mmr = (ctx.player_current_mmr + delta).max(0.0);
-
-    MmrResult { delta, new_mmr }
+fn calculate_mmr(ctx: &MmrContext, system: &dyn RatingSystem) -> MmrResult {
+    MmrResult::new(&ctx.player, system.rate(ctx))
 }
 
-fn parse_team_index(servant_raw: &Option<String>) -> Option<i32> {
-    let s = servant_raw.as_ref()?;
-    let cleaned = s.trim_matches('"');
-    let parts: Vec<&str> = cleaned.split(':').collect();
-    if parts.len() != 2 {
-        warn!("servant info format invalid: {}", s);
-        return None;
+/// Outcome a w3mmd flag is rated as, `None` for flags that are never rated.
+/// Win/loss totals shown elsewhere count games the same way.
+pub fn flag_outcome(flag: &str) -> Option<Outcome> {
+    match flag {
+        FLAG_WINNER => Some(Outcome::Win),
+        FLAG_DRAWER => Some(Outcome::Draw),
+        // Leavers always take the loss, even if their team went on to win
+        FLAG_LOSER | FLAG_LEAVER => Some(Outcome::Loss),
+        _ => None,
     }
-    parts[0].parse::<i32>().ok()
+}
+
+fn outcome_of(flag: &str) -> Outcome {
+    flag_outcome(flag).unwrap_or(Outcome::Loss)
 }
 
 // ── Computation (pure, no DB) ──
@@ -151,93 +483,120 @@ fn parse_team_index(servant_raw: &Option<String>) -> Option<i32> {
 struct PlayerMmrUpdate {
     name: String,
     category: String,
+    /// Result flag for the log, the w3mmd flag unless a rule changed the outcome.
     flag: String,
-    old_mmr: f64,
+    old_rating: Rating,
     result: MmrResult,
+    performance_modifier: f64,
     team_avg_mmr: f64,
     opponent_avg_mmr: f64,
 }
 
 fn compute_all_mmr_updates(
     players: &[GamePlayer],
-    current_scores: &HashMap<(String, String), f64>,
+    current_scores: &HashMap<(String, String), Rating>,
 ) -> Vec<PlayerMmrUpdate> {
-    const DEFAULT_MMR: f64 = 1000.0;
-
-    // Resolve each player's current MMR
-    let player_mmrs: Vec<f64> = players
+    // Resolve each player's current rating
+    let player_ratings: Vec<Rating> = players
         .iter()
         .map(|p| {
-            *current_scores
+            current_scores
                 .get(&(p.category.clone(), p.name.clone()))
-                .unwrap_or(&DEFAULT_MMR)
+                .copied()
+                .unwrap_or_else(|| rating_system(&p.category).initial())
         })
         .collect();
 
+    let rules = &CONFIG.mmr_rules;
+    let performance = &CONFIG.mmr_performance;
+
     // Group by team
-    let mut team_mmrs: HashMap<i32, Vec<f64>> = HashMap::new();
+    let mut teams: HashMap<i32, TeamRating> = HashMap::new();
+    let mut team_contributions: HashMap<i32, Vec<f64>> = HashMap::new();
     let mut player_team_ids: Vec<Option<i32>> = Vec::new();
+    let contributions: Vec<f64> = players.iter().map(|p| performance.contribution(p)).collect();
 
     for (i, p) in players.iter().enumerate() {
-        let team_id = parse_team_index(&p.servant_raw);
+        let team_id = p.vars.team;
         player_team_ids.push(team_id);
         if let Some(tid) = team_id {
-            team_mmrs.entry(tid).or_default().push(player_mmrs[i]);
+            teams.entry(tid).or_default().add(&player_ratings[i]);
+            team_contributions.entry(tid).or_default().push(contributions[i]);
         }
     }
 
-    // Compute team averages
-    let team_avgs: HashMap<i32, f64> = team_mmrs
-        .iter()
-        .map(|(&tid, mmrs)| {
-            let avg = mmrs.iter().sum::<f64>() / mmrs.len() as f64;
-            (tid, avg)
-        })
-        .collect();
-
     // Build updates
     let mut updates = Vec::new();
     for (i, p) in players.iter().enumerate() {
-        let current_mmr = player_mmrs[i];
+        let current = player_ratings[i];
         let team_id = player_team_ids[i];
 
-        let player_team_avg = team_id
-            .and_then(|tid| team_avgs.get(&tid).copied())
-            .unwrap_or(current_mmr);
-
-        // Opponent team avg: average of all other teams
-        let opponent_avg = if let Some(tid) = team_id {
-            let opponent_mmrs: Vec<f64> = team_avgs
-                .iter()
-                .filter(|&(&t, _)| t != tid)
-                .map(|(_, &avg)| avg)
-                .collect();
-            if opponent_mmrs.is_empty() {
-                player_team_avg
-            } else {
-                opponent_mmrs.iter().sum::<f64>() / opponent_mmrs.len() as f64
+        let mut own = TeamRating::default();
+        own.add(&current);
+
+        let team = team_id
+            .and_then(|tid| teams.get(&tid).copied())
+            .unwrap_or(own);
+
+        // Opponents: all other teams combined
+        let opponents = if let Some(tid) = team_id {
+            let mut opponents = TeamRating::default();
+            for (_, other) in teams.iter().filter(|&(&t, _)| t != tid) {
+                opponents.merge(other);
             }
+            if opponents.size == 0 { team } else { opponents }
         } else {
-            current_mmr
+            own
         };
 
         let ctx = MmrContext {
-            player_current_mmr: current_mmr,
-            player_team_avg_mmr: player_team_avg,
-            opponent_team_avg_mmr: opponent_avg,
-            is_winner: p.flag == "winner",
+            player: current,
+            team,
+            opponents,
+            outcome: outcome_of(&p.flag),
+        };
+
+        let mut result = calculate_mmr(&ctx, rating_system(&p.category));
+        let mut flag = p.flag.clone();
+
+        // Performance relative to teammates, leavers are never rewarded for it
+        let team_avg_contribution = team_id
+            .and_then(|tid| team_contributions.get(&tid))
+            .map_or(contributions[i], |c| c.iter().sum::<f64>() / c.len() as f64);
+        let performance_modifier = if p.flag == FLAG_LEAVER {
+            1.0
+        } else {
+            performance.modifier(contributions[i], team_avg_contribution)
         };
+        if performance_modifier != 1.0 {
+            let scale = if result.delta >= 0.0 {
+                performance_modifier
+            } else {
+                2.0 - performance_modifier
+            };
+            let delta = result.delta * scale;
+            result = result.with_delta(&current, delta);
+        }
 
-        let result = calculate_mmr(&ctx);
+        // Leaver penalty and protection for the teammates left behind
+        if p.flag == FLAG_LEAVER {
+            let delta = result.delta - rules.leaver_penalty;
+            result = result.with_delta(&current, delta);
+        } else if p.leaver_on_team && result.delta < 0.0 {
+            let delta = result.delta * rules.leaver_teammate_loss_factor;
+            result = result.with_delta(&current, delta);
+            flag = FLAG_PROTECTED.to_string();
+        }
 
         updates.push(PlayerMmrUpdate {
             name: p.name.clone(),
             category: p.category.clone(),
-            flag: p.flag.clone(),
-            old_mmr: current_mmr,
+            flag,
+            old_rating: current,
             result,
-            team_avg_mmr: player_team_avg,
-            opponent_avg_mmr: opponent_avg,
+            performance_modifier,
+            team_avg_mmr: team.avg_mmr(),
+            opponent_avg_mmr: opponents.avg_mmr(),
         });
     }
 
@@ -246,139 +605,206 @@ fn compute_all_mmr_updates(
 
 // ── Main processing ──
 
-async fn process_game_mmr(pool: &MySqlPool, game_id: i32) -> Result<(), sqlx::Error> {
+/// Rated players of the given games, all games if `game_ids` is `None`, with
+/// their vars read through each game's w3mmd schema.
+async fn fetch_game_players(
+    pool: &MySqlPool,
+    game_ids: Option<&[i32]>,
+) -> Result<Vec<GamePlayer>, sqlx::Error> {
+    let mut builder: QueryBuilder<MySql> = QueryBuilder::new(GAME_PLAYERS_QUERY);
+    if let Some(game_ids) = game_ids {
+        builder.push(" AND p.gameid IN (");
+        let mut separated = builder.separated(", ");
+        for id in game_ids {
+            separated.push_bind(*id);
+        }
+        separated.push_unseparated(")");
+    }
+    builder.push(" ORDER BY p.gameid, p.pid");
+    let mut players = builder.build_query_as::<GamePlayer>().fetch_all(pool).await?;
+
+    let index: HashMap<(i32, i32), usize> = players
+        .iter()
+        .enumerate()
+        .map(|(i, p)| ((p.gameid, p.pid), i))
+        .collect();
+    for var in w3mmd::fetch_vars(pool, game_ids).await? {
+        if let Some(&i) = index.get(&(var.gameid, var.pid)) {
+            let player = &mut players[i];
+            w3mmd::schema_for(&player.map, &player.category).apply(&var, &mut player.vars);
+        }
+    }
+
+    // Teammates of a leaver are protected even if leavers themselves are not rated
+    let leaver_teams: HashSet<(i32, i32)> = players
+        .iter()
+        .filter(|p| p.flag == FLAG_LEAVER)
+        .filter_map(|p| p.vars.team.map(|team| (p.gameid, team)))
+        .collect();
+    for player in &mut players {
+        player.leaver_on_team = player
+            .vars
+            .team
+            .is_some_and(|team| leaver_teams.contains(&(player.gameid, team)));
+    }
+    players.retain(|p| CONFIG.mmr_rules.is_rated(&p.flag));
+    Ok(players)
+}
+
+/// Rates a batch of games in id order and persists the result in one transaction.
+async fn process_games(pool: &MySqlPool, game_ids: &[i32]) -> Result<(), sqlx::Error> {
     // Stage 1: Fetch all data
 
-    let players = sqlx::query_as::<_, GamePlayer>(
-        r#"
-        SELECT
-          p.pid,
-          p.name,
-          p.category,
-          p.flag,
-          v.value_string AS servant_raw
-        FROM w3mmdplayers p
-        LEFT JOIN w3mmdvars v ON v.gameid = p.gameid AND v.pid = p.pid AND v.varname = 'servant'
-        WHERE p.gameid = ?
-          AND p.flag IN ('winner', 'loser')
-        "#,
-    )
-    .bind(game_id)
-    .fetch_all(pool)
-    .await?;
+    let mut players_by_game: HashMap<i32, Vec<GamePlayer>> = HashMap::new();
+    for p in fetch_game_players(pool, Some(game_ids)).await? {
+        players_by_game.entry(p.gameid).or_default().push(p);
+    }
+
+    // Current ratings of everyone in the batch, keyed by (category, name, server)
+    let keys: Vec<(String, String, String)> = players_by_game
+        .values()
+        .flatten()
+        .map(|p| (p.category.clone(), p.name.clone(), p.server.clone()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
 
-    if players.is_empty() {
-        // Mark as processed even if no eligible players
-        let mut tx = pool.begin().await?;
-        sqlx::query("INSERT INTO game_mmr_processed (gameid, processed_at) VALUES (?, ?)")
-            .bind(game_id)
-            .bind(Utc::now().naive_utc())
-            .execute(&mut *tx)
+    let mut ratings: HashMap<(String, String, String), Rating> = HashMap::new();
+    for chunk in keys.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+            r#"
+            SELECT category, name, server, score, deviation, volatility
+            FROM scores
+            WHERE (category, name, server) IN
+            "#,
+        );
+        builder.push_tuples(chunk, |mut b, (category, name, server)| {
+            b.push_bind(category).push_bind(name).push_bind(server);
+        });
+        let rows = builder
+            .build_query_as::<(String, String, String, f64, Option<f64>, Option<f64>)>()
+            .fetch_all(pool)
             .await?;
-        tx.commit().await?;
-        return Ok(());
+        for (category, name, server, mmr, deviation, volatility) in rows {
+            let rating = rating_system(&category).restore(mmr, deviation, volatility);
+            ratings.insert((category, name, server), rating);
+        }
     }
 
-    let server = sqlx::query_scalar::<_, String>(
-        "SELECT server FROM games WHERE id = ? LIMIT 1",
-    )
-    .bind(game_id)
-    .fetch_one(pool)
-    .await?;
+    // Stage 2: Compute (pure), in game order so later games see earlier results
+    let mut rated: HashSet<(String, String, String)> = HashSet::new();
+    let mut logs: Vec<(i32, String, PlayerMmrUpdate)> = Vec::new();
 
-    // Fetch current scores for all players in this game
-    let mut current_scores: HashMap<(String, String), f64> = HashMap::new();
-    for p in &players {
-        if current_scores.contains_key(&(p.category.clone(), p.name.clone())) {
+    for game_id in game_ids {
+        let Some(players) = players_by_game.get(game_id) else {
             continue;
-        }
-        let score = sqlx::query_scalar::<_, f64>(
-            "SELECT score FROM scores WHERE category = ? AND name = ? AND server = ? LIMIT 1",
-        )
-        .bind(&p.category)
-        .bind(&p.name)
-        .bind(&server)
-        .fetch_optional(pool)
-        .await?;
-
-        if let Some(s) = score {
-            current_scores.insert((p.category.clone(), p.name.clone()), s);
+        };
+        let server = &players[0].server;
+
+        let current_scores: HashMap<(String, String), Rating> = players
+            .iter()
+            .filter_map(|p| {
+                let key = (p.category.clone(), p.name.clone(), server.clone());
+                ratings
+                    .get(&key)
+                    .map(|rating| ((p.category.clone(), p.name.clone()), *rating))
+            })
+            .collect();
+
+        for u in compute_all_mmr_updates(players, &current_scores) {
+            debug!(
+                "MMR update: GameID {} {} ({}) delta={:+.1} new_mmr={:.1}",
+                game_id, u.name, u.flag, u.result.delta, u.result.rating.mmr
+            );
+            let key = (u.category.clone(), u.name.clone(), server.clone());
+            ratings.insert(key.clone(), u.result.rating);
+            rated.insert(key);
+            logs.push((*game_id, server.clone(), u));
         }
     }
 
-    // Stage 2: Compute (pure)
-    let updates = compute_all_mmr_updates(&players, &current_scores);
-
     // Stage 3: Persist
     let mut tx = pool.begin().await?;
     let now = Utc::now().naive_utc();
 
-    for u in &updates {
-        let is_new = !current_scores.contains_key(&(u.category.clone(), u.name.clone()));
-
-        if is_new {
-            sqlx::query(
-                "INSERT INTO scores (category, name, server, score) VALUES (?, ?, ?, ?)",
-            )
-            .bind(&u.category)
-            .bind(&u.name)
-            .bind(&server)
-            .bind(u.result.new_mmr)
-            .execute(&mut *tx)
-            .await?;
-        } else {
-            sqlx::query(
-                "UPDATE scores SET score = ? WHERE category = ? AND name = ? AND server = ?",
-            )
-            .bind(u.result.new_mmr)
-            .bind(&u.category)
-            .bind(&u.name)
-            .bind(&server)
-            .execute(&mut *tx)
-            .await?;
-        }
+    let rated: Vec<_> = rated.into_iter().collect();
+    for chunk in rated.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+            r#"
+            INSERT INTO scores
+                (category, name, server, score, deviation, volatility, last_played_at)
+            "#,
+        );
+        builder.push_values(chunk, |mut b, key| {
+            let rating = ratings[key];
+            b.push_bind(&key.0)
+                .push_bind(&key.1)
+                .push_bind(&key.2)
+                .push_bind(rating.mmr)
+                .push_bind(rating.deviation)
+                .push_bind(rating.volatility)
+                .push_bind(now);
+        });
+        builder.push(
+            r#"
+            ON DUPLICATE KEY UPDATE
+                score = VALUES(score),
+                deviation = VALUES(deviation),
+                volatility = VALUES(volatility),
+                last_played_at = VALUES(last_played_at),
+                decayed_at = NULL
+            "#,
+        );
+        builder.build().execute(&mut *tx).await?;
+    }
 
-        // Log the change
-        sqlx::query(
+    for chunk in logs.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
             r#"
             INSERT INTO score_change_logs
                 (gameid, category, name, server, mmr_before, mmr_after, mmr_delta,
-                 result_flag, team_avg_mmr, opponent_avg_mmr, created_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 deviation_before, deviation_after, volatility_before, volatility_after,
+                 result_flag, performance_modifier, team_avg_mmr, opponent_avg_mmr, created_at)
             "#,
-        )
-        .bind(game_id)
-        .bind(&u.category)
-        .bind(&u.name)
-        .bind(&server)
-        .bind(u.old_mmr)
-        .bind(u.result.new_mmr)
-        .bind(u.result.delta)
-        .bind(&u.flag)
-        .bind(u.team_avg_mmr)
-        .bind(u.opponent_avg_mmr)
-        .bind(now)
-        .execute(&mut *tx)
-        .await?;
-
-        info!(
-            "MMR update: {} ({}) delta={:+.1} new_mmr={:.1}",
-            u.name, u.flag, u.result.delta, u.result.new_mmr
         );
+        builder.push_values(chunk, |mut b, (game_id, server, u)| {
+            b.push_bind(*game_id)
+                .push_bind(&u.category)
+                .push_bind(&u.name)
+                .push_bind(server)
+                .push_bind(u.old_rating.mmr)
+                .push_bind(u.result.rating.mmr)
+                .push_bind(u.result.delta)
+                .push_bind(u.old_rating.deviation)
+                .push_bind(u.result.rating.deviation)
+                .push_bind(u.old_rating.volatility)
+                .push_bind(u.result.rating.volatility)
+                .push_bind(&u.flag)
+                .push_bind(u.performance_modifier)
+                .push_bind(u.team_avg_mmr)
+                .push_bind(u.opponent_avg_mmr)
+                .push_bind(now);
+        });
+        builder.build().execute(&mut *tx).await?;
     }
 
-    sqlx::query("INSERT INTO game_mmr_processed (gameid, processed_at) VALUES (?, ?)")
-        .bind(game_id)
-        .bind(now)
-        .execute(&mut *tx)
-        .await?;
+    // Games without eligible players are marked as processed too
+    for chunk in game_ids.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> =
+            QueryBuilder::new("INSERT INTO game_mmr_processed (gameid, processed_at) ");
+        builder.push_values(chunk, |mut b, game_id| {
+            b.push_bind(*game_id).push_bind(now);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
 
     tx.commit().await?;
 
     info!(
-        "Completed MMR processing GameID {} ({} players)",
-        game_id,
-        updates.len()
+        "Completed MMR processing of {} games up to GameID {} ({} rating changes)",
+        game_ids.len(),
+        game_ids.last().copied().unwrap_or_default(),
+        logs.len()
     );
     Ok(())
 }
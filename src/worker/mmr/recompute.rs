@@ -0,0 +1,392 @@
+//! Full MMR rebuild.
+//!
+//! Every game in `games` is replayed in chronological order through
+//! [`compute_all_mmr_updates`] into shadow copies of the MMR tables. The result
+//! can be compared against the live `scores` with [`diff_shadow`] and then
+//! swapped in with a single `RENAME TABLE`, so readers never see a half-built
+//! ranking. Voided games are skipped and season soft resets are replayed at
+//! each season start; inactivity decay is not replayed. The regular worker is
+//! paused while a replay or swap is running.
+//!
+//! The admin api runs the replay as a background job through
+//! [`start_recompute`], its progress is part of the worker status.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, Utc};
+use once_cell::sync::Lazy;
+use sqlx::{MySql, MySqlPool, QueryBuilder};
+use tracing::{error, info};
+
+use super::season::soft_reset;
+use super::{
+    compute_all_mmr_updates, fetch_game_players, GamePlayer, MmrAdminError, ProcessingGuard,
+    INSERT_CHUNK_SIZE,
+};
+use crate::model::mmr::{RecomputeJobStatus, RecomputeReport, RecomputeState, ScoreDiff};
+use crate::worker::rating::Rating;
+
+const DIFF_LIMIT: i64 = 100;
+
+static RECOMPUTE_JOB: Lazy<Mutex<RecomputeJobStatus>> = Lazy::new(Default::default);
+
+/// Live table and its shadow copy.
+const TABLES: [(&str, &str); 3] = [
+    ("scores", "scores_shadow"),
+    ("score_change_logs", "score_change_logs_shadow"),
+    ("game_mmr_processed", "game_mmr_processed_shadow"),
+];
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReplayGame {
+    id: i32,
+    server: String,
+    datetime: NaiveDateTime,
+    voided_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ReplayReset {
+    started_at: NaiveDateTime,
+    soft_reset_factor: f64,
+}
+
+struct ShadowLog {
+    gameid: i32,
+    category: String,
+    name: String,
+    server: String,
+    before: Rating,
+    after: Rating,
+    mmr_delta: f64,
+    result_flag: String,
+    performance_modifier: f64,
+    team_avg_mmr: f64,
+    opponent_avg_mmr: f64,
+    created_at: NaiveDateTime,
+}
+
+/// Rebuilds the shadow tables from scratch and returns the diff against `scores`.
+pub async fn replay_into_shadow(pool: &MySqlPool) -> Result<RecomputeReport, MmrAdminError> {
+    let _guard = ProcessingGuard::acquire()?;
+    replay(pool).await
+}
+
+/// Starts [`replay_into_shadow`] in the background, fails right away if
+/// processing is already running.
+pub fn start_recompute(pool: &'static MySqlPool) -> Result<(), MmrAdminError> {
+    let guard = ProcessingGuard::acquire()?;
+    update_job(|job| {
+        *job = RecomputeJobStatus {
+            state: RecomputeState::Running,
+            started_at: Some(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+    });
+
+    tokio::spawn(async move {
+        let result = replay(pool).await;
+        drop(guard);
+        update_job(|job| {
+            job.finished_at = Some(Utc::now().naive_utc());
+            match result {
+                Ok(report) => {
+                    job.state = RecomputeState::Finished;
+                    job.report = Some(report);
+                }
+                Err(err) => {
+                    error!("MMR recompute failed: {}", err);
+                    job.state = RecomputeState::Failed;
+                    job.error = Some(err.to_string());
+                }
+            }
+        });
+    });
+    Ok(())
+}
+
+/// The latest background recompute.
+pub fn recompute_status() -> RecomputeJobStatus {
+    RECOMPUTE_JOB.lock().map(|job| job.clone()).unwrap_or_default()
+}
+
+fn update_job(update: impl FnOnce(&mut RecomputeJobStatus)) {
+    if let Ok(mut job) = RECOMPUTE_JOB.lock() {
+        update(&mut job);
+    }
+}
+
+async fn replay(pool: &MySqlPool) -> Result<RecomputeReport, MmrAdminError> {
+    let games = sqlx::query_as::<_, ReplayGame>(
+        r#"
+        SELECT g.id, g.server, g.datetime, v.voided_at
+        FROM games g
+        LEFT JOIN game_mmr_voided v ON v.gameid = g.id
+        ORDER BY g.datetime, g.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let all_players = fetch_game_players(pool, None).await?;
+
+    let resets = sqlx::query_as::<_, ReplayReset>(
+        r#"
+        SELECT started_at, soft_reset_factor
+        FROM seasons
+        WHERE soft_reset_factor > 0
+        ORDER BY started_at, id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut players_by_game: HashMap<i32, Vec<GamePlayer>> = HashMap::new();
+    for p in all_players {
+        players_by_game.entry(p.gameid).or_default().push(p);
+    }
+
+    info!("MMR recompute: replaying {} games", games.len());
+
+    // Replay in memory, keyed by (category, name, server)
+    let mut ratings: HashMap<(String, String, String), Rating> = HashMap::new();
+    let mut last_played: HashMap<(String, String, String), NaiveDateTime> = HashMap::new();
+    let mut logs: Vec<ShadowLog> = Vec::new();
+    let mut resets = resets.into_iter().peekable();
+
+    for game in &games {
+        while let Some(reset) = resets.next_if(|r| r.started_at <= game.datetime) {
+            apply_reset(&mut ratings, &reset);
+        }
+
+        if game.voided_at.is_some() {
+            continue;
+        }
+        let Some(players) = players_by_game.get(&game.id) else {
+            continue;
+        };
+
+        let current_scores: HashMap<(String, String), Rating> = players
+            .iter()
+            .filter_map(|p| {
+                let key = (p.category.clone(), p.name.clone(), game.server.clone());
+                ratings
+                    .get(&key)
+                    .map(|rating| ((p.category.clone(), p.name.clone()), *rating))
+            })
+            .collect();
+
+        for u in compute_all_mmr_updates(players, &current_scores) {
+            let key = (u.category.clone(), u.name.clone(), game.server.clone());
+            last_played.insert(key.clone(), game.datetime);
+            ratings.insert(key, u.result.rating);
+            logs.push(ShadowLog {
+                gameid: game.id,
+                category: u.category,
+                name: u.name,
+                server: game.server.clone(),
+                before: u.old_rating,
+                after: u.result.rating,
+                mmr_delta: u.result.delta,
+                result_flag: u.flag,
+                performance_modifier: u.performance_modifier,
+                team_avg_mmr: u.team_avg_mmr,
+                opponent_avg_mmr: u.opponent_avg_mmr,
+                created_at: game.datetime,
+            });
+        }
+    }
+
+    // Seasons started after the last game still reset the live ratings
+    for reset in resets {
+        apply_reset(&mut ratings, &reset);
+    }
+
+    // Persist into freshly created shadow tables
+    for (live, shadow) in TABLES {
+        sqlx::query(&format!("DROP TABLE IF EXISTS {}", shadow))
+            .execute(pool)
+            .await?;
+        sqlx::query(&format!("CREATE TABLE {} LIKE {}", shadow, live))
+            .execute(pool)
+            .await?;
+    }
+
+    let score_rows: Vec<_> = ratings.iter().collect();
+    for chunk in score_rows.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+            r#"
+            INSERT INTO scores_shadow
+                (category, name, server, score, deviation, volatility, last_played_at)
+            "#,
+        );
+        builder.push_values(chunk, |mut b, (key, rating)| {
+            let (category, name, server) = key;
+            b.push_bind(category)
+                .push_bind(name)
+                .push_bind(server)
+                .push_bind(rating.mmr)
+                .push_bind(rating.deviation)
+                .push_bind(rating.volatility)
+                .push_bind(last_played.get(*key).copied());
+        });
+        builder.build().execute(pool).await?;
+    }
+
+    for chunk in logs.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> = QueryBuilder::new(
+            r#"
+            INSERT INTO score_change_logs_shadow
+                (gameid, category, name, server, mmr_before, mmr_after, mmr_delta,
+                 deviation_before, deviation_after, volatility_before, volatility_after,
+                 result_flag, performance_modifier, team_avg_mmr, opponent_avg_mmr, created_at)
+            "#,
+        );
+        builder.push_values(chunk, |mut b, log| {
+            b.push_bind(log.gameid)
+                .push_bind(&log.category)
+                .push_bind(&log.name)
+                .push_bind(&log.server)
+                .push_bind(log.before.mmr)
+                .push_bind(log.after.mmr)
+                .push_bind(log.mmr_delta)
+                .push_bind(log.before.deviation)
+                .push_bind(log.after.deviation)
+                .push_bind(log.before.volatility)
+                .push_bind(log.after.volatility)
+                .push_bind(&log.result_flag)
+                .push_bind(log.performance_modifier)
+                .push_bind(log.team_avg_mmr)
+                .push_bind(log.opponent_avg_mmr)
+                .push_bind(log.created_at);
+        });
+        builder.build().execute(pool).await?;
+    }
+
+    // Every replayed game counts as processed, including voided and unrated games
+    let now = Utc::now().naive_utc();
+    for chunk in games.chunks(INSERT_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<MySql> =
+            QueryBuilder::new("INSERT INTO game_mmr_processed_shadow (gameid, processed_at) ");
+        builder.push_values(chunk, |mut b, game| {
+            b.push_bind(game.id).push_bind(now);
+        });
+        builder.build().execute(pool).await?;
+    }
+
+    info!(
+        "MMR recompute: replayed {} games into shadow tables ({} ratings, {} changes)",
+        games.len(),
+        ratings.len(),
+        logs.len()
+    );
+
+    diff_shadow(pool, games.len()).await
+}
+
+fn apply_reset(ratings: &mut HashMap<(String, String, String), Rating>, reset: &ReplayReset) {
+    soft_reset(
+        ratings
+            .iter_mut()
+            .map(|((category, _, server), rating)| (category.as_str(), server.as_str(), rating))
+            .collect(),
+        reset.soft_reset_factor,
+    );
+}
+
+/// Compares the shadow `scores` against the live table.
+async fn diff_shadow(
+    pool: &MySqlPool,
+    games_replayed: usize,
+) -> Result<RecomputeReport, MmrAdminError> {
+    let (changed, added, removed) = sqlx::query_as::<_, (i64, i64, i64)>(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM scores_shadow s
+             JOIN scores c ON c.category = s.category AND c.name = s.name AND c.server = s.server
+             WHERE ABS(c.score - s.score) >= 0.05),
+            (SELECT COUNT(*) FROM scores_shadow s
+             LEFT JOIN scores c ON c.category = s.category AND c.name = s.name AND c.server = s.server
+             WHERE c.id IS NULL),
+            (SELECT COUNT(*) FROM scores c
+             LEFT JOIN scores_shadow s ON s.category = c.category AND s.name = c.name AND s.server = c.server
+             WHERE s.id IS NULL)
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // Largest movements first; players missing on one side sort as full-score changes
+    let diffs = sqlx::query_as::<_, ScoreDiff>(
+        r#"
+        SELECT category, name, server, current_score, replayed_score
+        FROM (
+            SELECT s.category, s.name, s.server, c.score AS current_score, s.score AS replayed_score
+            FROM scores_shadow s
+            LEFT JOIN scores c ON c.category = s.category AND c.name = s.name AND c.server = s.server
+            UNION ALL
+            SELECT c.category, c.name, c.server, c.score AS current_score, NULL AS replayed_score
+            FROM scores c
+            LEFT JOIN scores_shadow s ON s.category = c.category AND s.name = c.name AND s.server = c.server
+            WHERE s.id IS NULL
+        ) d
+        WHERE current_score IS NULL OR replayed_score IS NULL OR ABS(current_score - replayed_score) >= 0.05
+        ORDER BY ABS(COALESCE(replayed_score, 0) - COALESCE(current_score, 0)) DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(DIFF_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(RecomputeReport {
+        games_replayed,
+        changed,
+        added,
+        removed,
+        diffs,
+    })
+}
+
+/// Swaps the shadow tables in atomically and drops the previous live tables.
+pub async fn apply_shadow(pool: &MySqlPool) -> Result<(), MmrAdminError> {
+    let _guard = ProcessingGuard::acquire()?;
+
+    let shadow_tables = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM information_schema.tables
+        WHERE table_schema = DATABASE()
+          AND table_name IN ('scores_shadow', 'score_change_logs_shadow', 'game_mmr_processed_shadow')
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if shadow_tables != TABLES.len() as i64 {
+        return Err(MmrAdminError::NoShadowTables);
+    }
+
+    // A single RENAME TABLE statement is atomic across all tables
+    let renames = TABLES
+        .iter()
+        .map(|(live, shadow)| format!("{live} TO {live}_old, {shadow} TO {live}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    for (live, _) in TABLES {
+        sqlx::query(&format!("DROP TABLE IF EXISTS {}_old", live))
+            .execute(pool)
+            .await?;
+    }
+    sqlx::query(&format!("RENAME TABLE {}", renames))
+        .execute(pool)
+        .await?;
+    for (live, _) in TABLES {
+        sqlx::query(&format!("DROP TABLE {}_old", live))
+            .execute(pool)
+            .await?;
+    }
+
+    info!("MMR recompute: replayed ratings applied");
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! Seasons and inactivity decay.
+//!
+//! Starting a season snapshots the live `scores` into `season_scores` for the
+//! season being closed, then soft resets every rating towards the mean of its
+//! category and server. Inactive players lose `decay_per_day` points per day
+//! once they have not played for `decay_after_days`, down to `decay_floor`.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use tracing::info;
+
+use super::{MmrAdminError, ProcessingGuard};
+use crate::model::season::Season;
+use crate::settings::CONFIG;
+use crate::worker::rating::{rating_system, Rating, DEFAULT_MMR};
+
+const PRESEASON_NAME: &str = "Preseason";
+
+/// Season settings, `[seasons]` in settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SeasonRules {
+    /// Share of the distance to the mean removed at season start, 0 keeps ratings as-is.
+    pub soft_reset_factor: f64,
+    /// Days without a game before decay starts, 0 disables decay.
+    pub decay_after_days: u32,
+    pub decay_per_day: f64,
+    pub decay_floor: f64,
+}
+
+impl Default for SeasonRules {
+    fn default() -> Self {
+        SeasonRules {
+            soft_reset_factor: 0.5,
+            decay_after_days: 0,
+            decay_per_day: 5.0,
+            decay_floor: DEFAULT_MMR,
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ScoreRow {
+    id: i32,
+    category: String,
+    server: String,
+    score: f64,
+    deviation: Option<f64>,
+    volatility: Option<f64>,
+}
+
+/// Moves each rating `factor` of the way towards the mean of its (category, server)
+/// and widens its deviation by the same share towards the initial deviation.
+pub(super) fn soft_reset(entries: Vec<(&str, &str, &mut Rating)>, factor: f64) {
+    let mut totals: HashMap<(&str, &str), (f64, usize)> = HashMap::new();
+    for (category, server, rating) in &entries {
+        let total = totals.entry((*category, *server)).or_default();
+        total.0 += rating.mmr;
+        total.1 += 1;
+    }
+
+    for (category, server, rating) in entries {
+        let (sum, count) = totals[&(category, server)];
+        let mean = sum / count as f64;
+        rating.mmr = mean + (rating.mmr - mean) * (1.0 - factor);
+        if let (Some(deviation), Some(initial)) =
+            (rating.deviation, rating_system(category).initial().deviation)
+        {
+            rating.deviation = Some(deviation + (initial - deviation) * factor);
+        }
+    }
+}
+
+/// Closes the running season with a snapshot of `scores` and starts a new one.
+pub async fn start_season(pool: &MySqlPool, name: &str) -> Result<Season, MmrAdminError> {
+    let _guard = ProcessingGuard::acquire()?;
+    let factor = CONFIG.seasons.soft_reset_factor;
+
+    let mut tx = pool.begin().await?;
+    let now = Utc::now().naive_utc();
+
+    let current = sqlx::query_scalar::<_, i32>(
+        "SELECT id FROM seasons WHERE ended_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    // Ratings from before the first season are kept as a preseason snapshot
+    let closing_id = match current {
+        Some(id) => id,
+        None => sqlx::query(
+            r#"
+            INSERT INTO seasons (name, started_at)
+            VALUES (?, COALESCE((SELECT MIN(datetime) FROM games), ?))
+            "#,
+        )
+        .bind(PRESEASON_NAME)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_id() as i32,
+    };
+
+    sqlx::query("UPDATE seasons SET ended_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(closing_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO season_scores (season_id, category, name, server, score, deviation, volatility)
+        SELECT ?, category, name, server, score, deviation, volatility FROM scores
+        "#,
+    )
+    .bind(closing_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let season_id = sqlx::query(
+        "INSERT INTO seasons (name, started_at, soft_reset_factor) VALUES (?, ?, ?)",
+    )
+    .bind(name)
+    .bind(now)
+    .bind(factor)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id() as i32;
+
+    if factor > 0.0 {
+        let rows = sqlx::query_as::<_, ScoreRow>(
+            "SELECT id, category, server, score, deviation, volatility FROM scores",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut ratings: Vec<(ScoreRow, Rating)> = rows
+            .into_iter()
+            .map(|row| {
+                let rating = rating_system(&row.category).restore(
+                    row.score,
+                    row.deviation,
+                    row.volatility,
+                );
+                (row, rating)
+            })
+            .collect();
+
+        soft_reset(
+            ratings
+                .iter_mut()
+                .map(|(row, rating)| (row.category.as_str(), row.server.as_str(), rating))
+                .collect(),
+            factor,
+        );
+
+        for (row, rating) in &ratings {
+            sqlx::query("UPDATE scores SET score = ?, deviation = ? WHERE id = ?")
+                .bind(rating.mmr)
+                .bind(rating.deviation)
+                .bind(row.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    info!("Season {} \"{}\" started (season {} closed)", season_id, name, closing_id);
+    Ok(Season {
+        id: season_id,
+        name: name.to_string(),
+        started_at: now,
+        ended_at: None,
+    })
+}
+
+/// Applies the inactivity decay for every full day that has not been decayed yet.
+pub(super) async fn apply_decay(pool: &MySqlPool) -> Result<u64, sqlx::Error> {
+    let rules = &CONFIG.seasons;
+    if rules.decay_after_days == 0 || rules.decay_per_day <= 0.0 {
+        return Ok(0);
+    }
+
+    // Decay runs from the end of the grace period or from the last decayed day
+    let decay_from = format!(
+        "GREATEST(last_played_at + INTERVAL {} DAY, COALESCE(decayed_at, last_played_at))",
+        rules.decay_after_days
+    );
+    let days = format!("FLOOR(TIMESTAMPDIFF(SECOND, {}, ?) / 86400)", decay_from);
+    let query = format!(
+        r#"
+        UPDATE scores
+        SET score = GREATEST(?, score - ? * {days}),
+            decayed_at = {decay_from} + INTERVAL {days} DAY
+        WHERE last_played_at IS NOT NULL
+          AND score > ?
+          AND {days} >= 1
+        "#
+    );
+
+    let now = Utc::now().naive_utc();
+    let result = sqlx::query(&query)
+        .bind(rules.decay_floor)
+        .bind(rules.decay_per_day)
+        .bind(now)
+        .bind(now)
+        .bind(rules.decay_floor)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() > 0 {
+        info!("MMR decay applied to {} inactive players", result.rows_affected());
+    }
+    Ok(result.rows_affected())
+}
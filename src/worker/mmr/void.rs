@@ -0,0 +1,115 @@
+//! Voiding games that should not count towards MMR (remakes, host disconnects, abuse).
+//!
+//! Voiding subtracts the game's logged score, deviation and volatility changes
+//! from `scores`, marks those log rows voided and marks the game processed so
+//! the worker never picks it up. Ratings of later games that were computed on
+//! top of the voided result are left as-is; run a full recompute if those need
+//! to be corrected as well.
+
+use chrono::Utc;
+use sqlx::MySqlPool;
+use tracing::info;
+
+use super::{MmrAdminError, ProcessingGuard};
+
+#[derive(Debug, sqlx::FromRow)]
+struct LoggedChange {
+    category: String,
+    name: String,
+    server: String,
+    mmr_delta: f64,
+    /// `None` for Elo, whose ratings have no deviation or volatility.
+    deviation_delta: Option<f64>,
+    volatility_delta: Option<f64>,
+}
+
+/// Voids `game_id` and returns how many players had their score reverted.
+pub async fn void_game(
+    pool: &MySqlPool,
+    game_id: i32,
+    reason: &str,
+) -> Result<usize, MmrAdminError> {
+    let _guard = ProcessingGuard::acquire()?;
+
+    let exists = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM games WHERE id = ?")
+        .bind(game_id)
+        .fetch_one(pool)
+        .await?;
+    if exists == 0 {
+        return Err(MmrAdminError::GameNotFound);
+    }
+
+    let mut tx = pool.begin().await?;
+    let now = Utc::now().naive_utc();
+
+    let inserted = sqlx::query(
+        "INSERT IGNORE INTO game_mmr_voided (gameid, reason, voided_at) VALUES (?, ?, ?)",
+    )
+    .bind(game_id)
+    .bind(reason)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(MmrAdminError::AlreadyVoided);
+    }
+
+    let changes = sqlx::query_as::<_, LoggedChange>(
+        r#"
+        SELECT category, name, server, mmr_delta,
+               deviation_after - deviation_before AS deviation_delta,
+               volatility_after - volatility_before AS volatility_delta
+        FROM score_change_logs
+        WHERE gameid = ? AND voided_at IS NULL
+        "#,
+    )
+    .bind(game_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for c in &changes {
+        sqlx::query(
+            r#"
+            UPDATE scores
+            SET score = GREATEST(score - ?, 0),
+                deviation = COALESCE(deviation - ?, deviation),
+                volatility = COALESCE(volatility - ?, volatility)
+            WHERE category = ? AND name = ? AND server = ?
+            "#,
+        )
+        .bind(c.mmr_delta)
+        .bind(c.deviation_delta)
+        .bind(c.volatility_delta)
+        .bind(&c.category)
+        .bind(&c.name)
+        .bind(&c.server)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Kept for the audit trail, readers skip voided rows
+    sqlx::query(
+        "UPDATE score_change_logs SET voided_at = ? WHERE gameid = ? AND voided_at IS NULL",
+    )
+    .bind(now)
+    .bind(game_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Unprocessed games are marked too, so the worker skips them
+    sqlx::query("INSERT IGNORE INTO game_mmr_processed (gameid, processed_at) VALUES (?, ?)")
+        .bind(game_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    info!(
+        "Voided GameID {} ({} players reverted): {}",
+        game_id,
+        changes.len(),
+        reason
+    );
+    Ok(changes.len())
+}
@@ -1 +1,4 @@
 pub mod mmr;
+pub mod rating;
+pub mod room_feed;
+pub mod room_watcher;
@@ -0,0 +1,110 @@
+use super::{MmrContext, Rating, RatingSystem, DEFAULT_MMR};
+
+/// Classic Elo on team averages.
+pub struct Elo;
+
+impl Elo {
+    // K-factor: base points at stake per game.
+    // Higher K = faster rating movement, lower K = more stable ratings.
+    const K: f64 = 32.0;
+}
+
+impl RatingSystem for Elo {
+    fn initial(&self) -> Rating {
+        Rating {
+            mmr: DEFAULT_MMR,
+            deviation: None,
+            volatility: None,
+        }
+    }
+
+    fn rate(&self, ctx: &MmrContext) -> Rating {
+        // Expected win probability using logistic function:
+        //   E = 1 / (1 + 10^((opponent_avg - team_avg) / 400))
+        //
+        // When teams are equal: E = 0.5
+        // When team is 200 pts stronger: E ≈ 0.76
+        // When team is 200 pts weaker:   E ≈ 0.24
+        let rating_diff = ctx.opponents.avg_mmr() - ctx.team.avg_mmr();
+        let expected = 1.0 / (1.0 + 10.0_f64.powf(rating_diff / 400.0));
+
+        let actual = ctx.outcome.score();
+
+        // Delta = K * (actual - expected), a draw counts as 0.5
+        //
+        // Examples (equal teams, expected=0.5):
+        //   winner: 32 * (1.0 - 0.5) = +16
+        //   loser:  32 * (0.0 - 0.5) = -16
+        //
+        // Weak team (expected=0.24) beats strong team:
+        //   winner: 32 * (1.0 - 0.24) = +24.3  (big reward)
+        //   loser:  32 * (0.0 - 0.76) = -24.3  (big penalty)
+        //
+        // Strong team (expected=0.76) beats weak team:
+        //   winner: 32 * (1.0 - 0.76) = +7.7   (small reward)
+        //   loser:  32 * (0.0 - 0.24) = -7.7   (small penalty)
+        let delta = Self::K * (actual - expected);
+
+        Rating {
+            mmr: (ctx.player.mmr + delta).max(0.0),
+            deviation: None,
+            volatility: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::rating::{Outcome, TeamRating};
+
+    fn side(mmrs: &[f64]) -> TeamRating {
+        let mut team = TeamRating::default();
+        for &mmr in mmrs {
+            team.add(&Rating {
+                mmr,
+                deviation: None,
+                volatility: None,
+            });
+        }
+        team
+    }
+
+    fn delta(team: &[f64], opponents: &[f64], outcome: Outcome) -> f64 {
+        let player = Rating {
+            mmr: team[0],
+            deviation: None,
+            volatility: None,
+        };
+        let ctx = MmrContext {
+            player,
+            team: side(team),
+            opponents: side(opponents),
+            outcome,
+        };
+        Elo.rate(&ctx).mmr - player.mmr
+    }
+
+    #[test]
+    fn win_and_loss_are_symmetric() {
+        assert_eq!(delta(&[1000.0], &[1000.0], Outcome::Win), 16.0);
+        assert_eq!(delta(&[1000.0], &[1000.0], Outcome::Loss), -16.0);
+
+        let win = delta(&[1200.0, 1000.0], &[900.0, 1000.0], Outcome::Win);
+        let loss = delta(&[1200.0, 1000.0], &[900.0, 1000.0], Outcome::Loss);
+        assert!((win - loss - Elo::K).abs() < 1e-9);
+        assert!(win > 0.0 && win < 16.0);
+    }
+
+    #[test]
+    fn is_zero_sum() {
+        let winner = delta(&[1100.0], &[950.0], Outcome::Win);
+        let loser = delta(&[950.0], &[1100.0], Outcome::Loss);
+        assert!((winner + loser).abs() < 1e-9);
+
+        let draw = delta(&[1100.0], &[950.0], Outcome::Draw);
+        let other_draw = delta(&[950.0], &[1100.0], Outcome::Draw);
+        assert!((draw + other_draw).abs() < 1e-9);
+        assert!(draw < 0.0);
+    }
+}
@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+
+use super::{MmrContext, Rating, RatingSystem, DEFAULT_MMR};
+
+/// Glicko-2 with one rating period per game. The opposing side is treated as a
+/// single composite opponent with the average rating and deviation of its members.
+pub struct Glicko2;
+
+impl Glicko2 {
+    /// Conversion factor between the Glicko and Glicko-2 scales.
+    const SCALE: f64 = 173.7178;
+    const INITIAL_DEVIATION: f64 = 350.0;
+    const INITIAL_VOLATILITY: f64 = 0.06;
+    /// Constrains the volatility change over time, 0.3 ~ 1.2 is reasonable.
+    const TAU: f64 = 0.5;
+    const CONVERGENCE: f64 = 0.000001;
+
+    fn g(phi: f64) -> f64 {
+        1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+    }
+
+    /// Iterative volatility update (step 5 of the Glicko-2 paper, Illinois algorithm).
+    fn new_volatility(sigma: f64, phi: f64, v: f64, delta: f64) -> f64 {
+        let a = (sigma * sigma).ln();
+        let tau2 = Self::TAU * Self::TAU;
+        let f = |x: f64| {
+            let ex = x.exp();
+            let d = phi * phi + v + ex;
+            ex * (delta * delta - phi * phi - v - ex) / (2.0 * d * d) - (x - a) / tau2
+        };
+
+        let mut big_a = a;
+        let mut big_b = if delta * delta > phi * phi + v {
+            (delta * delta - phi * phi - v).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * Self::TAU) < 0.0 {
+                k += 1.0;
+            }
+            a - k * Self::TAU
+        };
+
+        let mut f_a = f(big_a);
+        let mut f_b = f(big_b);
+        while (big_b - big_a).abs() > Self::CONVERGENCE {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0.0 {
+                big_a = big_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            big_b = big_c;
+            f_b = f_c;
+        }
+
+        (big_a / 2.0).exp()
+    }
+
+    /// One rating period against `results`, each `(mu_j, phi_j, score)` on the
+    /// Glicko-2 scale (steps 3 to 7 of the paper). Returns the new mu, phi and sigma.
+    fn update(mu: f64, phi: f64, sigma: f64, results: &[(f64, f64, f64)]) -> (f64, f64, f64) {
+        // Estimated variance and improvement based on the game outcomes
+        let mut v_inv = 0.0;
+        let mut improvement = 0.0;
+        for &(mu_j, phi_j, score) in results {
+            let g = Self::g(phi_j);
+            let expected = 1.0 / (1.0 + (-g * (mu - mu_j)).exp());
+            v_inv += g * g * expected * (1.0 - expected);
+            improvement += g * (score - expected);
+        }
+        let v = 1.0 / v_inv;
+        let delta = v * improvement;
+
+        let new_sigma = Self::new_volatility(sigma, phi, v, delta);
+        let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+        let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+        let new_mu = mu + new_phi * new_phi * improvement;
+        (new_mu, new_phi, new_sigma)
+    }
+}
+
+impl RatingSystem for Glicko2 {
+    fn initial(&self) -> Rating {
+        Rating {
+            mmr: DEFAULT_MMR,
+            deviation: Some(Self::INITIAL_DEVIATION),
+            volatility: Some(Self::INITIAL_VOLATILITY),
+        }
+    }
+
+    fn rate(&self, ctx: &MmrContext) -> Rating {
+        let deviation = ctx.player.deviation.unwrap_or(Self::INITIAL_DEVIATION);
+        let sigma = ctx.player.volatility.unwrap_or(Self::INITIAL_VOLATILITY);
+
+        // Convert to the Glicko-2 scale, centred on the default MMR
+        let mu = (ctx.player.mmr - DEFAULT_MMR) / Self::SCALE;
+        let phi = deviation / Self::SCALE;
+        let mu_j = (ctx.opponents.avg_mmr() - DEFAULT_MMR) / Self::SCALE;
+        let phi_j = ctx.opponents.avg_deviation() / Self::SCALE;
+
+        let (new_mu, new_phi, new_sigma) =
+            Self::update(mu, phi, sigma, &[(mu_j, phi_j, ctx.outcome.score())]);
+
+        Rating {
+            mmr: (new_mu * Self::SCALE + DEFAULT_MMR).max(0.0),
+            deviation: Some((new_phi * Self::SCALE).min(Self::INITIAL_DEVIATION)),
+            volatility: Some(new_sigma),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_the_worked_example_of_the_paper() {
+        // Glickman, "Example of the Glicko-2 system", section 3
+        let to_mu = |r: f64| (r - 1500.0) / Glicko2::SCALE;
+        let to_phi = |rd: f64| rd / Glicko2::SCALE;
+        let results = [
+            (to_mu(1400.0), to_phi(30.0), 1.0),
+            (to_mu(1550.0), to_phi(100.0), 0.0),
+            (to_mu(1700.0), to_phi(300.0), 0.0),
+        ];
+
+        let (mu, phi, sigma) = Glicko2::update(to_mu(1500.0), to_phi(200.0), 0.06, &results);
+
+        assert!((mu * Glicko2::SCALE + 1500.0 - 1464.06).abs() < 0.01);
+        assert!((phi * Glicko2::SCALE - 151.52).abs() < 0.01);
+        assert!((sigma - 0.05999).abs() < 0.00001);
+    }
+}
@@ -0,0 +1,143 @@
+//! Rating algorithms used by the MMR worker.
+//!
+//! Every algorithm keeps its state in a [`Rating`]: `mmr` is the value stored in
+//! `scores.score`, while `deviation` and `volatility` map onto the optional
+//! `scores.deviation` / `scores.volatility` columns for algorithms that track
+//! uncertainty. The algorithm is chosen per map category in `settings.toml`.
+
+mod elo;
+mod glicko2;
+mod trueskill;
+
+use serde::Deserialize;
+
+use crate::settings::CONFIG;
+
+pub use elo::Elo;
+pub use glicko2::Glicko2;
+pub use trueskill::TrueSkill;
+
+/// Rating assigned to players without a `scores` row.
+pub const DEFAULT_MMR: f64 = 1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub mmr: f64,
+    pub deviation: Option<f64>,
+    pub volatility: Option<f64>,
+}
+
+/// Aggregated ratings of one side of a game.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TeamRating {
+    pub size: usize,
+    pub mmr_sum: f64,
+    /// Sum of the squared deviations of the members.
+    pub variance_sum: f64,
+}
+
+impl TeamRating {
+    pub fn add(&mut self, rating: &Rating) {
+        let deviation = rating.deviation.unwrap_or(0.0);
+        self.size += 1;
+        self.mmr_sum += rating.mmr;
+        self.variance_sum += deviation * deviation;
+    }
+
+    pub fn merge(&mut self, other: &TeamRating) {
+        self.size += other.size;
+        self.mmr_sum += other.mmr_sum;
+        self.variance_sum += other.variance_sum;
+    }
+
+    pub fn avg_mmr(&self) -> f64 {
+        if self.size == 0 {
+            return DEFAULT_MMR;
+        }
+        self.mmr_sum / self.size as f64
+    }
+
+    /// Root mean square of the members' deviations.
+    pub fn avg_deviation(&self) -> f64 {
+        if self.size == 0 {
+            return 0.0;
+        }
+        (self.variance_sum / self.size as f64).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl Outcome {
+    /// Actual score of the outcome as used by Elo and Glicko-2.
+    pub fn score(self) -> f64 {
+        match self {
+            Outcome::Win => 1.0,
+            Outcome::Loss => 0.0,
+            Outcome::Draw => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MmrContext {
+    pub player: Rating,
+    pub team: TeamRating,
+    pub opponents: TeamRating,
+    pub outcome: Outcome,
+}
+
+pub trait RatingSystem: Send + Sync {
+    /// Rating given to a player on their first game in a category.
+    fn initial(&self) -> Rating;
+
+    /// Rebuilds a rating from a `scores` row. Columns the row does not have yet
+    /// (e.g. after switching a category from Elo) fall back to the initial values.
+    fn restore(&self, mmr: f64, deviation: Option<f64>, volatility: Option<f64>) -> Rating {
+        let initial = self.initial();
+        Rating {
+            mmr,
+            deviation: initial.deviation.map(|d| deviation.unwrap_or(d)),
+            volatility: initial.volatility.map(|v| volatility.unwrap_or(v)),
+        }
+    }
+
+    /// Returns the player's rating after the game described by `ctx`.
+    fn rate(&self, ctx: &MmrContext) -> Rating;
+
+    /// Expected probability of `team` beating `opponents`, Elo's logistic curve
+    /// on the team averages unless the algorithm has its own model.
+    fn win_probability(&self, team: &TeamRating, opponents: &TeamRating) -> f64 {
+        let rating_diff = opponents.avg_mmr() - team.avg_mmr();
+        1.0 / (1.0 + 10.0_f64.powf(rating_diff / 400.0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RatingAlgorithm {
+    #[default]
+    Elo,
+    Glicko2,
+    TrueSkill,
+}
+
+impl RatingAlgorithm {
+    pub fn system(self) -> &'static dyn RatingSystem {
+        match self {
+            RatingAlgorithm::Elo => &Elo,
+            RatingAlgorithm::Glicko2 => &Glicko2,
+            RatingAlgorithm::TrueSkill => &TrueSkill,
+        }
+    }
+}
+
+/// Rating system configured for a map category.
+pub fn rating_system(category: &str) -> &'static dyn RatingSystem {
+    CONFIG.rating_algorithm(category).system()
+}
@@ -0,0 +1,165 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use super::{MmrContext, Outcome, Rating, RatingSystem, TeamRating, DEFAULT_MMR};
+
+/// Two-team TrueSkill. Team performance is the sum of its members, so uneven
+/// team sizes are accounted for instead of being averaged away.
+pub struct TrueSkill;
+
+impl TrueSkill {
+    /// Standard TrueSkill parameters (mu = 25, sigma = 25 / 3) scaled so that
+    /// mu matches the default MMR.
+    const INITIAL_SIGMA: f64 = DEFAULT_MMR / 3.0;
+    /// Performance variability of a single game.
+    const BETA: f64 = Self::INITIAL_SIGMA / 2.0;
+    /// Dynamics factor, keeps sigma from collapsing to zero.
+    const TAU: f64 = Self::INITIAL_SIGMA / 100.0;
+    /// Inverse normal CDF of (p + 1) / 2 for a 10% draw probability p, scales
+    /// the draw margin used when a game ends in a draw.
+    const DRAW_MARGIN_Z: f64 = 0.1257;
+
+    fn pdf(x: f64) -> f64 {
+        (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+    }
+
+    fn cdf(x: f64) -> f64 {
+        0.5 * erfc(-x / SQRT_2)
+    }
+
+    /// Mean additive truncated Gaussian correction for a win with margin `t`.
+    fn v(t: f64) -> f64 {
+        let denom = Self::cdf(t);
+        if denom < f64::EPSILON {
+            return -t;
+        }
+        Self::pdf(t) / denom
+    }
+
+    /// Variance multiplicative truncated Gaussian correction for a win with margin `t`.
+    fn w(t: f64) -> f64 {
+        let v = Self::v(t);
+        (v * (v + t)).clamp(0.0, 1.0)
+    }
+
+    /// Mean correction for a draw with performance difference `t` and draw margin `e`.
+    fn v_draw(t: f64, e: f64) -> f64 {
+        let denom = Self::cdf(e - t) - Self::cdf(-e - t);
+        if denom < f64::EPSILON {
+            return if t < 0.0 { -t - e } else { -t + e };
+        }
+        (Self::pdf(-e - t) - Self::pdf(e - t)) / denom
+    }
+
+    /// Variance correction for a draw with performance difference `t` and draw margin `e`.
+    fn w_draw(t: f64, e: f64) -> f64 {
+        let denom = Self::cdf(e - t) - Self::cdf(-e - t);
+        if denom < f64::EPSILON {
+            return 1.0;
+        }
+        let v = Self::v_draw(t, e);
+        let tails = (e - t) * Self::pdf(e - t) + (e + t) * Self::pdf(e + t);
+        (v * v + tails / denom).clamp(0.0, 1.0)
+    }
+}
+
+impl RatingSystem for TrueSkill {
+    fn initial(&self) -> Rating {
+        Rating {
+            mmr: DEFAULT_MMR,
+            deviation: Some(Self::INITIAL_SIGMA),
+            volatility: None,
+        }
+    }
+
+    fn rate(&self, ctx: &MmrContext) -> Rating {
+        let sigma = ctx.player.deviation.unwrap_or(Self::INITIAL_SIGMA);
+        let variance = sigma * sigma + Self::TAU * Self::TAU;
+
+        let players = (ctx.team.size + ctx.opponents.size) as f64;
+        let c2 = ctx.team.variance_sum
+            + ctx.opponents.variance_sum
+            + players * (Self::TAU * Self::TAU + Self::BETA * Self::BETA);
+        let c = c2.sqrt();
+
+        // Performance difference from the player's side, corrections are mirrored
+        // for the losing side
+        let t = (ctx.team.mmr_sum - ctx.opponents.mmr_sum) / c;
+        let (v, w) = match ctx.outcome {
+            Outcome::Win => (Self::v(t), Self::w(t)),
+            Outcome::Loss => (-Self::v(-t), Self::w(-t)),
+            Outcome::Draw => {
+                let e = Self::DRAW_MARGIN_Z * players.sqrt() * Self::BETA / c;
+                (Self::v_draw(t, e), Self::w_draw(t, e))
+            }
+        };
+
+        let new_mu = ctx.player.mmr + variance / c * v;
+        let new_variance = variance * (1.0 - variance / c2 * w);
+
+        Rating {
+            mmr: new_mu.max(0.0),
+            deviation: Some(new_variance.sqrt()),
+            volatility: None,
+        }
+    }
+
+    fn win_probability(&self, team: &TeamRating, opponents: &TeamRating) -> f64 {
+        let players = (team.size + opponents.size) as f64;
+        let c2 = team.variance_sum + opponents.variance_sum + players * Self::BETA * Self::BETA;
+        Self::cdf((team.mmr_sum - opponents.mmr_sum) / c2.sqrt())
+    }
+}
+
+/// Complementary error function (Numerical Recipes `erfcc`, |error| < 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t * (-z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277)))))))))
+        .exp();
+    if x >= 0.0 { r } else { 2.0 - r }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(outcome: Outcome) -> (Rating, Rating) {
+        let player = TrueSkill.initial();
+        let mut team = TeamRating::default();
+        team.add(&player);
+        let mut opponents = TeamRating::default();
+        opponents.add(&TrueSkill.initial());
+        let ctx = MmrContext {
+            player,
+            team,
+            opponents,
+            outcome,
+        };
+        (player, TrueSkill.rate(&ctx))
+    }
+
+    #[test]
+    fn win_raises_mu_and_lowers_sigma() {
+        let (before, after) = rate(Outcome::Win);
+        assert!(after.mmr > before.mmr);
+        assert!(after.deviation.unwrap() < before.deviation.unwrap());
+
+        let (before, after) = rate(Outcome::Loss);
+        assert!(after.mmr < before.mmr);
+        assert!(after.deviation.unwrap() < before.deviation.unwrap());
+    }
+
+    #[test]
+    fn draw_between_equal_players_keeps_mu() {
+        let (before, after) = rate(Outcome::Draw);
+        assert!((after.mmr - before.mmr).abs() < 1e-9);
+        assert!(after.deviation.unwrap() < before.deviation.unwrap());
+    }
+}
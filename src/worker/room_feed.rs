@@ -0,0 +1,199 @@
+use serenity::all::{
+    ChannelId, CreateEmbed, CreateEmbedFooter, CreateMessage, EditMessage, GetMessages, MessageId,
+    Timestamp,
+};
+use serenity::http::Http;
+use tokio::sync::broadcast;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+
+use crate::model::room::RoomInfo;
+use crate::settings::CONFIG;
+use crate::worker::room_watcher::{RoomSnapshot, RoomWatcher};
+
+/// Minimum time between status message edits, to stay clear of Discord rate limits.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+const STATUS_TITLE: &str = "Room Status";
+const STATUS_DESCRIPTION_LIMIT: usize = 4000;
+
+pub fn start_room_feed_worker(room_watcher: RoomWatcher, mut shutdown_rx: broadcast::Receiver<()>) {
+    if CONFIG.discord_room_feed_channel_id == 0 {
+        info!("Room feed disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let http = Http::new(&CONFIG.discord_token);
+        let channel_id = ChannelId::new(CONFIG.discord_room_feed_channel_id);
+        let mut snapshot_rx = room_watcher.subscribe_snapshot();
+        let mut feed = RoomFeed {
+            status_message: find_status_message(&http, channel_id).await,
+            last_rooms: None,
+        };
+
+        let mut ticker = interval(UPDATE_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {},
+                _ = shutdown_rx.recv() => {
+                    info!("Room feed worker received shutdown signal");
+                    break;
+                }
+            }
+
+            // Always publish on the first round so the status message exists
+            let changed = snapshot_rx.has_changed().unwrap_or(false);
+            if !changed && feed.status_message.is_some() {
+                continue;
+            }
+            let snapshot = snapshot_rx.borrow_and_update().clone();
+
+            if let Err(e) = feed.update(&http, channel_id, snapshot).await {
+                error!("Room feed worker error: {}", e);
+            }
+        }
+        info!("Room feed worker shutdown complete");
+    });
+}
+
+struct RoomFeed {
+    status_message: Option<MessageId>,
+    last_rooms: Option<Vec<RoomInfo>>,
+}
+
+impl RoomFeed {
+    async fn update(
+        &mut self,
+        http: &Http,
+        channel_id: ChannelId,
+        snapshot: RoomSnapshot,
+    ) -> Result<(), serenity::Error> {
+        let rooms = match snapshot {
+            Some(status) => status.rooms.clone(),
+            // The watcher already logged the read error, keep the last status
+            None => return Ok(()),
+        };
+
+        if self.last_rooms.as_ref() == Some(&rooms) && self.status_message.is_some() {
+            return Ok(());
+        }
+
+        let embed = build_status_embed(&rooms);
+        self.status_message = match self.status_message {
+            Some(message_id) => {
+                match channel_id
+                    .edit_message(http, message_id, EditMessage::new().embed(embed.clone()))
+                    .await
+                {
+                    Ok(message) => Some(message.id),
+                    Err(e) => {
+                        // The status message was probably deleted, post a new one
+                        warn!("Room feed can't edit status message: {}", e);
+                        let message = channel_id
+                            .send_message(http, CreateMessage::new().embed(embed))
+                            .await?;
+                        Some(message.id)
+                    }
+                }
+            }
+            None => {
+                let message = channel_id
+                    .send_message(http, CreateMessage::new().embed(embed))
+                    .await?;
+                Some(message.id)
+            }
+        };
+
+        // Remember the rooms before notifying, a failed notification must not
+        // make every new room resend on the next tick
+        let previous = self.last_rooms.replace(rooms.clone());
+        if CONFIG.room_feed_notify_new_games && let Some(previous) = &previous {
+            for room in new_rooms(previous, &rooms) {
+                let embed = CreateEmbed::new()
+                    .title("New Game Created")
+                    .color(0x3498db)
+                    .description(&room.room_name)
+                    .timestamp(Timestamp::now());
+                if let Err(e) = channel_id
+                    .send_message(http, CreateMessage::new().embed(embed))
+                    .await
+                {
+                    warn!("Room feed can't notify new game {}: {}", room.room_name, e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reuses the status message posted before a restart, if it is still in the channel.
+async fn find_status_message(http: &Http, channel_id: ChannelId) -> Option<MessageId> {
+    let current_user = match http.get_current_user().await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!("Room feed can't get current user: {}", e);
+            return None;
+        }
+    };
+
+    let messages = match channel_id
+        .messages(http, GetMessages::new().limit(50))
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            warn!("Room feed can't read channel history: {}", e);
+            return None;
+        }
+    };
+
+    messages
+        .into_iter()
+        .find(|m| {
+            m.author.id == current_user.id
+                && m.embeds
+                    .first()
+                    .and_then(|e| e.title.as_deref())
+                    .is_some_and(|title| title == STATUS_TITLE)
+        })
+        .map(|m| m.id)
+}
+
+fn new_rooms<'a>(old: &[RoomInfo], new: &'a [RoomInfo]) -> Vec<&'a RoomInfo> {
+    new.iter()
+        .filter(|room| !old.iter().any(|o| o.room_id == room.room_id))
+        .collect()
+}
+
+fn build_status_embed(rooms: &[RoomInfo]) -> CreateEmbed {
+    let mut description = String::new();
+    if rooms.is_empty() {
+        description.push_str("No open rooms");
+    }
+    for room in rooms {
+        let mut line = format!("**{}** - {} players", room.room_name, room.player_count);
+        if !room.players.is_empty() {
+            line.push_str(&format!(": {}", room.players.join(", ")));
+        }
+        line.push('\n');
+        if description.len() + line.len() > STATUS_DESCRIPTION_LIMIT {
+            description.push_str("...");
+            break;
+        }
+        description.push_str(&line);
+    }
+
+    let players: u32 = rooms.iter().map(|r| r.player_count as u32).sum();
+
+    CreateEmbed::new()
+        .title(STATUS_TITLE)
+        .color(0x2ecc71)
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!(
+            "{} rooms, {} players",
+            rooms.len(),
+            players
+        )))
+        .timestamp(Timestamp::now())
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use tokio::sync::{broadcast, watch};
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+
+use crate::model::room::{RoomInfo, ServerStatus};
+use crate::settings::CONFIG;
+use crate::util::server_dat::{self, ParseError};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// `None` while the log file can't be read.
+pub type RoomSnapshot = Option<Arc<ServerStatus>>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RoomEvent {
+    Added { room: RoomInfo },
+    Removed { room_id: u16 },
+    Updated { room: RoomInfo },
+}
+
+/// Handle to the single `server.dat` watcher shared by the HTTP API and the Discord feed.
+#[derive(Clone)]
+pub struct RoomWatcher {
+    snapshot: watch::Receiver<RoomSnapshot>,
+    events: broadcast::Sender<RoomEvent>,
+}
+
+impl RoomWatcher {
+    pub fn snapshot(&self) -> RoomSnapshot {
+        self.snapshot.borrow().clone()
+    }
+
+    pub fn subscribe_snapshot(&self) -> watch::Receiver<RoomSnapshot> {
+        self.snapshot.clone()
+    }
+
+    pub fn subscribe_events(&self) -> broadcast::Receiver<RoomEvent> {
+        self.events.subscribe()
+    }
+}
+
+pub fn start_room_watcher(mut shutdown_rx: broadcast::Receiver<()>) -> RoomWatcher {
+    let (initial, initial_errors) = read_snapshot();
+    let (snapshot_tx, snapshot_rx) = watch::channel(initial.clone());
+    let (events_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
+    let watcher = RoomWatcher {
+        snapshot: snapshot_rx,
+        events: events_tx.clone(),
+    };
+
+    tokio::spawn(async move {
+        let mut last_modified = modified_time();
+        let mut last_rooms = initial;
+        let mut last_errors = initial_errors;
+        let mut ticker = interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {},
+                _ = shutdown_rx.recv() => {
+                    info!("Room watcher received shutdown signal");
+                    break;
+                }
+            }
+
+            let modified = modified_time();
+            if modified.is_some() && modified == last_modified && last_rooms.is_some() {
+                continue;
+            }
+            last_modified = modified;
+
+            let (rooms, errors) = read_snapshot();
+            // Only report parse errors when they change, the file is rewritten every few seconds
+            if errors != last_errors {
+                for error in &errors {
+                    warn!("server.dat parse error: {}", error);
+                }
+                last_errors = errors;
+            }
+            if rooms == last_rooms {
+                continue;
+            }
+
+            if let (Some(old), Some(new)) = (&last_rooms, &rooms) {
+                for event in diff_rooms(&old.rooms, &new.rooms) {
+                    // No subscribers is not an error
+                    let _ = events_tx.send(event);
+                }
+            }
+
+            last_rooms = rooms.clone();
+            snapshot_tx.send_replace(rooms);
+        }
+        info!("Room watcher shutdown complete");
+    });
+
+    watcher
+}
+
+fn modified_time() -> Option<SystemTime> {
+    fs::metadata(&CONFIG.bn_log_path)
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+fn read_snapshot() -> (RoomSnapshot, Vec<ParseError>) {
+    match server_dat::read(&CONFIG.bn_log_path) {
+        Ok((status, errors)) => (Some(Arc::new(status)), errors),
+        Err(e) => {
+            warn!("Room watcher can't read log: {}", e);
+            (None, Vec::new())
+        }
+    }
+}
+
+fn diff_rooms(old: &[RoomInfo], new: &[RoomInfo]) -> Vec<RoomEvent> {
+    let mut events = Vec::new();
+
+    for room in old {
+        if !new.iter().any(|r| r.room_id == room.room_id) {
+            events.push(RoomEvent::Removed {
+                room_id: room.room_id,
+            });
+        }
+    }
+
+    for room in new {
+        match old.iter().find(|r| r.room_id == room.room_id) {
+            None => events.push(RoomEvent::Added { room: room.clone() }),
+            Some(previous) if previous != room => {
+                events.push(RoomEvent::Updated { room: room.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+
+    events
+}